@@ -34,9 +34,9 @@ fn day1(input: String) {
 
 fn day2(input: String) {
     let mut limits = Cubes::new();
-    limits.insert(Color::Red, 12);
-    limits.insert(Color::Green, 13);
-    limits.insert(Color::Blue, 14);
+    limits.insert(Color::RED, 12);
+    limits.insert(Color::GREEN, 13);
+    limits.insert(Color::BLUE, 14);
 
     let game = Game::new(&input, limits).expect("Failed to create game");
     let ids_sum = game.get_ids_sum();
@@ -56,13 +56,11 @@ fn day3(input: String) {
 }
 
 fn day4(input: String) {
-    let mut cards = ScratchCards::from_str(&input).expect("Failed to create scratchcards!");
+    let cards = ScratchCards::from_str(&input).expect("Failed to create scratchcards!");
     let total = cards.get_points_worth().expect("Failed to calculate total");
     println!("Total points: {total}");
 
-    let copies = cards
-        .calculate_copies_and_get_total()
-        .expect("Failed to calculate copies");
+    let copies = cards.get_total_cards();
     println!("Total cards won: {copies}");
 }
 
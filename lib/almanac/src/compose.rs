@@ -0,0 +1,232 @@
+use crate::{interval::IntervalSet, Category, CategoryMap, TranslateError, TranslationMap};
+use std::ops::Range;
+
+/// A segment of a piecewise-linear function, shifting every value in the source range by the offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    source: Range<u64>,
+    offset: i128,
+}
+
+impl Segment {
+    /// Create a new segment
+    #[inline(always)]
+    pub const fn new(source: Range<u64>, offset: i128) -> Self {
+        Self { source, offset }
+    }
+
+    /// Get the source range of the segment
+    #[inline(always)]
+    pub const fn source(&self) -> &Range<u64> {
+        &self.source
+    }
+
+    /// Get the offset of the segment
+    #[inline(always)]
+    pub const fn offset(&self) -> &i128 {
+        &self.offset
+    }
+
+    /// Get the destination range of the segment
+    #[inline(always)]
+    pub fn destination(&self) -> Range<u64> {
+        self.shift(self.source.start)..self.shift(self.source.end)
+    }
+
+    /// Shift a value by the offset, clamping it to the domain
+    ///
+    /// Composed maps never need the clamp, as they're built from lines that fit in `u64`.
+    #[inline(always)]
+    fn shift(&self, value: u64) -> u64 {
+        let shifted = i128::from(value) + self.offset;
+        u64::try_from(shifted.max(0)).unwrap_or(u64::MAX)
+    }
+}
+
+/// A chain of maps composed into a single piecewise-linear function
+///
+/// The segments are sorted, don't overlap, and cover the whole domain of `u64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposedMap {
+    source: Category,
+    destination: Category,
+    segments: Vec<Segment>,
+}
+
+impl ComposedMap {
+    /// Create the identity function for a category
+    #[inline(always)]
+    fn identity(category: &Category) -> Self {
+        Self {
+            source: category.clone(),
+            destination: category.clone(),
+            segments: vec![Segment::new(0..u64::MAX, 0)],
+        }
+    }
+
+    /// Create the function of a single map, from the pieces it splits the whole domain into
+    ///
+    /// Fails with [`TranslateError::Overflow`] if a line running past the end of `u64` is used, so
+    /// every segment maps into the domain and composing never has to clamp.
+    fn from_map(map: &CategoryMap) -> Result<Self, TranslateError> {
+        let segments = map
+            .split(&IntervalSet::from(0..u64::MAX))?
+            .into_iter()
+            .map(|piece| {
+                let offset = i128::from(piece.destination().start) - i128::from(piece.source().start);
+                Segment::new(piece.source().clone(), offset)
+            })
+            .collect();
+
+        Ok(Self {
+            source: map.source().clone(),
+            destination: map.destination().clone(),
+            segments,
+        }
+        .normalized())
+    }
+
+    /// Compose with the next function, applying it after this one
+    fn then(&self, next: &Self) -> Self {
+        let segments = self
+            .segments
+            .iter()
+            .flat_map(|segment| {
+                let image = segment.destination();
+
+                // Find the first segment of the next function overlapping the image
+                let first = next.segments.partition_point(|n| n.source.end <= image.start);
+
+                next.segments[first..]
+                    .iter()
+                    .take_while(move |n| n.source.start < image.end)
+                    .filter_map(move |n| {
+                        let start = image.start.max(n.source.start);
+                        let end = image.end.min(n.source.end);
+
+                        // Map the overlap back to the source of this function
+                        let source = u64::try_from(i128::from(start) - segment.offset).ok()?
+                            ..u64::try_from(i128::from(end) - segment.offset).ok()?;
+
+                        Some(Segment::new(source, segment.offset + n.offset))
+                    })
+            })
+            .collect();
+
+        Self {
+            source: self.source.clone(),
+            destination: next.destination.clone(),
+            segments,
+        }
+        .normalized()
+    }
+
+    /// Merge neighbouring segments with the same offset, and drop empty ones
+    fn normalized(mut self) -> Self {
+        let mut segments: Vec<Segment> = Vec::with_capacity(self.segments.len());
+
+        self.segments.sort_by_key(|segment| segment.source.start);
+
+        for segment in self.segments {
+            if segment.source.is_empty() {
+                continue;
+            }
+
+            match segments.last_mut() {
+                Some(last) if last.offset == segment.offset && last.source.end == segment.source.start => {
+                    last.source.end = segment.source.end;
+                }
+                _ => segments.push(segment),
+            }
+        }
+
+        self.segments = segments;
+        self
+    }
+
+    /// Get the source category
+    #[inline(always)]
+    pub const fn source(&self) -> &Category {
+        &self.source
+    }
+
+    /// Get the destination category
+    #[inline(always)]
+    pub const fn destination(&self) -> &Category {
+        &self.destination
+    }
+
+    /// Get the segments of the function
+    #[inline(always)]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Get the segment containing a value
+    #[inline(always)]
+    fn segment_of(&self, value: u64) -> Option<&Segment> {
+        let index = self.segments.partition_point(|segment| segment.source.end <= value);
+        self.segments
+            .get(index)
+            .filter(|segment| segment.source.contains(&value))
+    }
+
+    /// Translate a value, using a single binary search
+    #[inline(always)]
+    pub fn translate(&self, value: u64) -> u64 {
+        self.segment_of(value).map_or(value, |segment| segment.shift(value))
+    }
+
+    /// Translate a range, returning the translated pieces in source order
+    pub fn translate_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let first = self
+            .segments
+            .partition_point(|segment| segment.source.end <= range.start);
+
+        self.segments[first..]
+            .iter()
+            .take_while(|segment| segment.source.start < range.end)
+            .map(|segment| {
+                let start = range.start.max(segment.source.start);
+                let end = range.end.min(segment.source.end);
+                segment.shift(start)..segment.shift(end)
+            })
+            .collect()
+    }
+
+    /// Get the lowest translated value of a range
+    #[inline(always)]
+    pub fn lowest(&self, range: Range<u64>) -> Option<u64> {
+        self.translate_range(range)
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .map(|range| range.start)
+            .min()
+    }
+}
+
+/// Writes the function in the almanac map format, leaving out identity segments
+impl std::fmt::Display for ComposedMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-to-{} map:", self.source, self.destination)?;
+
+        for segment in self.segments.iter().filter(|segment| segment.offset != 0) {
+            let destination = segment.destination();
+            let length = segment.source.end - segment.source.start;
+            write!(f, "\n{} {} {}", destination.start, segment.source.start, length)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TranslationMap {
+    /// Compose the chain of maps from one category to another into a single function
+    pub fn compose(&self, from: &Category, to: &Category) -> Result<ComposedMap, TranslateError> {
+        let path = self.path(from, to)?;
+
+        path.iter().try_fold(ComposedMap::identity(from), |composed, map| {
+            Ok(composed.then(&ComposedMap::from_map(map)?))
+        })
+    }
+}
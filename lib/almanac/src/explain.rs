@@ -0,0 +1,249 @@
+use crate::{interval::IntervalSet, Almanac, Category, CategoryMap, MapLine, TranslateError, TranslationMap};
+use std::ops::Range;
+
+/// A single stage of a translated value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage {
+    category: Category,
+    value: u64,
+    line: Option<MapLine>,
+}
+
+impl Stage {
+    /// Get the category of the stage
+    #[inline(always)]
+    pub const fn category(&self) -> &Category {
+        &self.category
+    }
+
+    /// Get the value at the stage
+    #[inline(always)]
+    pub const fn value(&self) -> &u64 {
+        &self.value
+    }
+
+    /// Get the map line applied to reach the stage, or `None` for identity
+    #[inline(always)]
+    pub const fn line(&self) -> &Option<MapLine> {
+        &self.line
+    }
+}
+
+/// The chain of stages a value goes through, from the source category to the destination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValuePath(Vec<Stage>);
+
+impl ValuePath {
+    /// Get the stages, starting with the untranslated value
+    #[inline(always)]
+    pub fn stages(&self) -> &[Stage] {
+        &self.0
+    }
+
+    /// Get the final value
+    #[inline(always)]
+    pub fn value(&self) -> Option<&u64> {
+        self.0.last().map(Stage::value)
+    }
+}
+
+impl std::fmt::Display for ValuePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:>12} | {:>12} | Applied", "Category", "Value")?;
+        write!(f, "{}", "-".repeat(40))?;
+
+        for (index, stage) in self.0.iter().enumerate() {
+            let applied = match (index, &stage.line) {
+                (0, _) => String::new(),
+                (_, Some(line)) => line.to_string(),
+                (_, None) => "identity".to_string(),
+            };
+
+            let row = format!("{:>12} | {:>12} | {}", stage.category.name(), stage.value, applied);
+            write!(f, "\n{}", row.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A range of values, split into the pieces each map line translates it into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSplit {
+    category: Category,
+    range: Range<u64>,
+    line: Option<MapLine>,
+    children: Vec<RangeSplit>,
+}
+
+impl RangeSplit {
+    /// Get the category of the range
+    #[inline(always)]
+    pub const fn category(&self) -> &Category {
+        &self.category
+    }
+
+    /// Get the range of values
+    #[inline(always)]
+    pub const fn range(&self) -> &Range<u64> {
+        &self.range
+    }
+
+    /// Get the map line applied to reach the range, or `None` for identity
+    #[inline(always)]
+    pub const fn line(&self) -> &Option<MapLine> {
+        &self.line
+    }
+
+    /// Get the pieces the range is split into by the next map
+    #[inline(always)]
+    pub fn children(&self) -> &[RangeSplit] {
+        &self.children
+    }
+
+    /// Get the final ranges, at the leaves of the tree
+    pub fn leaves(&self) -> IntervalSet {
+        if self.children.is_empty() {
+            return self.range.clone().into();
+        }
+
+        self.children
+            .iter()
+            .flat_map(|child| child.leaves().ranges().to_vec())
+            .collect()
+    }
+
+    /// Split the range with the first map, then split each piece with the rest
+    fn split(
+        category: Category,
+        range: Range<u64>,
+        line: Option<MapLine>,
+        path: &[&CategoryMap],
+    ) -> Result<Self, TranslateError> {
+        let children = match path.split_first() {
+            Some((map, next)) => map
+                .split(&range.clone().into())?
+                .into_iter()
+                .map(|piece| {
+                    Self::split(
+                        map.destination().clone(),
+                        piece.destination().clone(),
+                        *piece.line(),
+                        next,
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            category,
+            range,
+            line,
+            children,
+        })
+    }
+
+    /// Write the rows of the table, indenting each level of the tree
+    fn write_rows(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let applied = match (depth, &self.line) {
+            (0, _) => String::new(),
+            (_, Some(line)) => line.to_string(),
+            (_, None) => "identity".to_string(),
+        };
+        let category = format!("{}{}", "  ".repeat(depth), self.category);
+        let range = format!("{}..{}", self.range.start, self.range.end);
+
+        let row = format!("{category:<24} | {range:>24} | {applied}");
+        write!(f, "\n{}", row.trim_end())?;
+
+        self.children
+            .iter()
+            .try_for_each(|child| child.write_rows(f, depth + 1))
+    }
+}
+
+impl std::fmt::Display for RangeSplit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<24} | {:>24} | Applied", "Category", "Range")?;
+        write!(f, "{}", "-".repeat(64))?;
+
+        self.write_rows(f, 0)
+    }
+}
+
+impl CategoryMap {
+    /// Get the line translating a value, or `None` if it passes through unchanged
+    #[inline(always)]
+    pub fn line_of(&self, value: u64) -> Option<&MapLine> {
+        self.translations()
+            .find(|(source, _)| source.contains(&value))
+            .map(|(_, line)| line)
+    }
+}
+
+impl TranslationMap {
+    /// Explain how a value is translated from one category to another
+    ///
+    /// Fails like [`TranslationMap::translate`] does, if the value is `u64::MAX` or hits a line
+    /// running past the end of `u64`.
+    pub fn explain(&self, from: &Category, to: &Category, value: u64) -> Result<ValuePath, TranslateError> {
+        let path = self.path(from, to)?;
+        value.checked_add(1).ok_or(TranslateError::OutOfDomain(value))?;
+
+        let mut stages = vec![Stage {
+            category: from.clone(),
+            value,
+            line: None,
+        }];
+
+        for map in path {
+            let current = stages.last().map_or(value, |stage| stage.value);
+
+            // Every translated value stays below u64::MAX, so the range is never empty
+            let piece = map
+                .split(&(current..current + 1).into())?
+                .into_iter()
+                .next()
+                .ok_or(TranslateError::OutOfDomain(current))?;
+
+            stages.push(Stage {
+                category: map.destination().clone(),
+                value: piece.destination().start,
+                line: *piece.line(),
+            });
+        }
+
+        Ok(ValuePath(stages))
+    }
+
+    /// Explain how a range is split up while it's translated from one category to another
+    ///
+    /// Fails if any of the range hits a line running past the end of `u64`.
+    #[inline(always)]
+    pub fn explain_range(
+        &self,
+        from: &Category,
+        to: &Category,
+        range: Range<u64>,
+    ) -> Result<RangeSplit, TranslateError> {
+        let path = self.path(from, to)?;
+        RangeSplit::split(from.clone(), range, None, &path)
+    }
+}
+
+impl Almanac {
+    /// Explain how a seed is translated to its location
+    #[inline(always)]
+    pub fn explain(&self, seed: u64) -> Result<ValuePath, TranslateError> {
+        self.translation
+            .explain(&self.seed_category, &self.location_category, seed)
+    }
+
+    /// Explain how a range of seeds is split up while it's translated to locations
+    #[inline(always)]
+    pub fn explain_range(&self, range: Range<u64>) -> Result<RangeSplit, TranslateError> {
+        self.translation
+            .explain_range(&self.seed_category, &self.location_category, range)
+    }
+}
@@ -0,0 +1,190 @@
+use std::ops::Range;
+
+/// A set of values, stored as sorted ranges that neither overlap nor touch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl IntervalSet {
+    /// Create a new, empty set
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Get the ranges of the set, sorted and merged
+    #[inline(always)]
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    /// Check if the set has no values
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Get the number of values in the set
+    #[inline(always)]
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|range| range.end - range.start).sum()
+    }
+
+    /// Get the lowest value of the set
+    #[inline(always)]
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    /// Check if the set contains a value
+    #[inline(always)]
+    pub fn contains(&self, value: u64) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges.get(index).is_some_and(|range| range.contains(&value))
+    }
+
+    /// Sort the ranges, dropping empty ones and merging any that overlap or touch
+    fn normalize(mut ranges: Vec<Range<u64>>) -> Self {
+        ranges.retain(|range| !range.is_empty());
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Get the values in either set
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    /// Get the values in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            // Move past whichever range ends first
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Get the values in this set, but not in the other
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+
+        for range in &self.ranges {
+            let mut start = range.start;
+
+            // Skip ranges of the other set that end before this one
+            while other.ranges.get(j).is_some_and(|b| b.end <= start) {
+                j += 1;
+            }
+
+            let mut k = j;
+
+            while let Some(b) = other.ranges.get(k).filter(|b| b.start < range.end) {
+                if start < b.start {
+                    ranges.push(start..b.start);
+                }
+
+                start = start.max(b.end);
+                k += 1;
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Shift every value by an offset, dropping any values that fall outside of `u64`
+    pub fn shift(&self, offset: i128) -> Self {
+        let clamp = |value: i128| u64::try_from(value.clamp(0, i128::from(u64::MAX))).unwrap_or(u64::MAX);
+
+        Self::normalize(
+            self.ranges
+                .iter()
+                .map(|range| clamp(i128::from(range.start) + offset)..clamp(i128::from(range.end) + offset))
+                .collect(),
+        )
+    }
+
+    /// Split the ranges of the set at each of the boundaries
+    ///
+    /// e.g. `{0..10}` split at `5` gives `0..5` and `5..10`
+    pub fn split_at(&self, boundaries: &[u64]) -> Vec<Range<u64>> {
+        let mut boundaries = boundaries.to_vec();
+        boundaries.sort_unstable();
+
+        self.ranges
+            .iter()
+            .flat_map(|range| {
+                let first = boundaries.partition_point(|boundary| *boundary <= range.start);
+
+                let cuts = boundaries[first..]
+                    .iter()
+                    .take_while(|boundary| **boundary < range.end)
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                std::iter::once(range.start)
+                    .chain(cuts.iter().copied())
+                    .zip(cuts.iter().copied().chain(std::iter::once(range.end)))
+                    .filter(|(start, end)| start < end)
+                    .map(|(start, end)| start..end)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+impl From<Range<u64>> for IntervalSet {
+    #[inline(always)]
+    fn from(value: Range<u64>) -> Self {
+        Self::normalize(vec![value])
+    }
+}
+
+impl FromIterator<Range<u64>> for IntervalSet {
+    #[inline(always)]
+    fn from_iter<T: IntoIterator<Item = Range<u64>>>(iter: T) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+impl std::fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|range| format!("{}..{}", range.start, range.end))
+            .collect::<Vec<_>>();
+
+        write!(f, "{{{}}}", ranges.join(", "))
+    }
+}
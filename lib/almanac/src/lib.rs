@@ -1,4 +1,27 @@
-use std::{collections::BTreeMap, num::ParseIntError, ops::Range, str::FromStr};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    num::ParseIntError,
+    ops::Range,
+    str::FromStr,
+};
+
+/// Map composition module
+pub mod compose;
+
+/// Interval set module
+pub mod interval;
+
+/// Reverse translation module
+pub mod reverse;
+
+/// Almanac validation module
+pub mod validation;
+
+/// Translation explanation module
+pub mod explain;
+
+use compose::ComposedMap;
+use interval::IntervalSet;
 
 /// An error that can occur when parsing an Almanac
 #[derive(Debug)]
@@ -6,6 +29,7 @@ pub enum ParseAlmanacError {
     ParseInt(ParseIntError),
     GetSeeds,
     InvalidMapKey(String),
+    InvalidMapLine(String),
     NoKeyFound,
 }
 
@@ -23,56 +47,80 @@ impl std::fmt::Display for ParseAlmanacError {
             Self::ParseInt(e) => write!(f, "Failed to parse integer: {}", e),
             Self::GetSeeds => write!(f, "Failed to get seeds"),
             Self::InvalidMapKey(e) => write!(f, "Invalid map key: {}", e),
+            Self::InvalidMapLine(e) => write!(f, "Invalid map line: {}", e),
             Self::NoKeyFound => write!(f, "No key found"),
         }
     }
 }
 
-/// Values for each "layer" in the translation map
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
-pub enum ValueType {
-    Seed,
-    Soil,
-    Fertilizer,
-    Water,
-    Light,
-    Temperature,
-    Humidity,
-    Location,
+/// An error that can occur when translating between categories
+#[derive(Debug)]
+pub enum TranslateError {
+    NoPath(Category, Category),
+    Overflow(Category, Category, MapLine),
+    OutOfDomain(u64),
+}
+
+impl std::error::Error for TranslateError {}
+
+impl std::fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoPath(from, to) => write!(f, "No path from '{}' to '{}'", from, to),
+            Self::Overflow(from, to, line) => {
+                write!(
+                    f,
+                    "Line '{}' of the {}-to-{} map runs past the end of u64",
+                    line, from, to
+                )
+            }
+            Self::OutOfDomain(value) => write!(f, "Value {} is outside of the translatable domain", value),
+        }
+    }
+}
+
+/// A category of values in the almanac, e.g. `seed` or `soil`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category(String);
+
+impl Category {
+    /// Create a new category
+    #[inline(always)]
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+
+    /// Get the name of the category
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.0
+    }
 }
 
-impl ValueType {
-    /// Get the next variant in the enum
+impl From<&str> for Category {
     #[inline(always)]
-    pub const fn next_variant(&self) -> Option<Self> {
-        use ValueType::*;
-        let next = match *self {
-            Seed => Soil,
-            Soil => Fertilizer,
-            Fertilizer => Water,
-            Water => Light,
-            Light => Temperature,
-            Temperature => Humidity,
-            Humidity => Location,
-            Location => return None,
-        };
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
 
-        Some(next)
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 /// A value in the translation map
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TranslationValue {
-    typ: ValueType,
     range: Range<u64>,
 }
 
 impl TranslationValue {
     /// Create a new TranslationValue
     #[inline(always)]
-    pub const fn new(typ: ValueType, from: u64, to: u64) -> Self {
-        Self { typ, range: from..to }
+    pub const fn new(from: u64, to: u64) -> Self {
+        Self { range: from..to }
     }
 }
 
@@ -83,89 +131,352 @@ impl PartialOrd for TranslationValue {
     }
 }
 
+/// Orders by the start of the range, then by the end
 impl Ord for TranslationValue {
     #[inline(always)]
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        use std::cmp::Ordering::*;
+        (self.range.start, self.range.end).cmp(&(other.range.start, other.range.end))
+    }
+}
 
-        let type_compare = self.typ.cmp(&other.typ);
+/// A line of a map, as written in the almanac
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MapLine {
+    destination: u64,
+    source: u64,
+    length: u64,
+}
 
-        if type_compare != Equal {
-            return type_compare;
+impl MapLine {
+    /// Create a new map line
+    #[inline(always)]
+    pub const fn new(destination: u64, source: u64, length: u64) -> Self {
+        Self {
+            destination,
+            source,
+            length,
         }
+    }
 
-        // self range is before other range, so it is less
-        if self.range.end <= other.range.start {
-            Less
-        // self range is after other range, so it is greater
-        } else if self.range.start >= other.range.end {
-            Greater
-        } else {
-            Equal
-        }
+    /// Get the start of the destination range
+    #[inline(always)]
+    pub const fn destination(&self) -> &u64 {
+        &self.destination
+    }
+
+    /// Get the start of the source range
+    #[inline(always)]
+    pub const fn source(&self) -> &u64 {
+        &self.source
+    }
+
+    /// Get the length of the ranges
+    #[inline(always)]
+    pub const fn length(&self) -> &u64 {
+        &self.length
+    }
+
+    /// Get the source range, clipped to `u64`
+    #[inline(always)]
+    pub const fn source_range(&self) -> Range<u64> {
+        self.source..self.source.saturating_add(self.length)
+    }
+
+    /// Get the destination range, clipped to `u64`
+    #[inline(always)]
+    pub const fn destination_range(&self) -> Range<u64> {
+        self.destination..self.destination.saturating_add(self.length)
+    }
+
+    /// Check if either range runs past the end of `u64`
+    #[inline(always)]
+    pub const fn overflows(&self) -> bool {
+        self.source.checked_add(self.length).is_none() || self.destination.checked_add(self.length).is_none()
+    }
+}
+
+impl std::fmt::Display for MapLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.destination, self.source, self.length)
     }
 }
 
+/// A piece of a set translated by a single map line, or passed through unchanged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapPiece {
+    source: Range<u64>,
+    destination: Range<u64>,
+    line: Option<MapLine>,
+}
+
+impl MapPiece {
+    /// Get the source range of the piece
+    #[inline(always)]
+    pub const fn source(&self) -> &Range<u64> {
+        &self.source
+    }
+
+    /// Get the destination range of the piece
+    #[inline(always)]
+    pub const fn destination(&self) -> &Range<u64> {
+        &self.destination
+    }
+
+    /// Get the map line that translated the piece, or `None` if it passed through unchanged
+    #[inline(always)]
+    pub const fn line(&self) -> &Option<MapLine> {
+        &self.line
+    }
+}
+
+/// A map translating values from one category to another, e.g. `seed-to-soil`
 #[derive(Debug)]
-pub struct TranslationMap(BTreeMap<TranslationValue, TranslationValue>);
+pub struct CategoryMap {
+    source: Category,
+    destination: Category,
+    lines: Vec<MapLine>,
+    translations: BTreeMap<TranslationValue, MapLine>,
+}
 
-impl TranslationMap {
-    /// Add a translation to the map
+impl CategoryMap {
+    /// Create a new, empty map
+    #[inline(always)]
+    pub fn new(source: Category, destination: Category) -> Self {
+        Self {
+            source,
+            destination,
+            lines: Vec::new(),
+            translations: BTreeMap::new(),
+        }
+    }
+
+    /// Get the source category of the map
+    #[inline(always)]
+    pub const fn source(&self) -> &Category {
+        &self.source
+    }
+
+    /// Get the destination category of the map
+    #[inline(always)]
+    pub const fn destination(&self) -> &Category {
+        &self.destination
+    }
+
+    /// Get the lines of the map, in the order they were written
     #[inline(always)]
-    fn add_translation(&mut self, from: TranslationValue, to: TranslationValue) {
-        self.0.insert(from, to);
+    pub fn lines(&self) -> &[MapLine] {
+        &self.lines
     }
 
-    /// Get the location of a seed
+    /// Add a line to the map
+    ///
+    /// If a line with the same source range was already added, it keeps precedence.
     #[inline(always)]
-    pub fn get_location_of_seed(&self, seed: u64) -> Option<u64> {
-        self.walk(ValueType::Seed, seed..seed)
+    fn add_line(&mut self, line: MapLine) {
+        let source = line.source_range();
+
+        self.lines.push(line);
+        self.translations
+            .entry(TranslationValue::new(source.start, source.end))
+            .or_insert(line);
     }
 
-    /// Translate a range from one ValueType to another
+    /// Get the translations of the map, keyed by source range, in the order they take precedence
     #[inline(always)]
-    fn translate_range(&self, source_type: ValueType, source_range: Range<u64>) -> Option<u64> {
-        self.walk(source_type, source_range)
+    pub fn translations(&self) -> impl Iterator<Item = (&Range<u64>, &MapLine)> {
+        self.translations.iter().map(|(key, line)| (&key.range, line))
     }
 
-    /// Walk the translation map
-    fn walk(&self, from: ValueType, range: Range<u64>) -> Option<u64> {
-        // Iterate over keys for current valuetype (Seed, Soil, etc..) where the range is hitting
-        let keys = self
+    /// Split a set of values into the pieces each translation applies to, sorted by source
+    ///
+    /// Values not covered by any translation are passed through unchanged. If translations overlap,
+    /// the one starting first wins. Values hitting a line that runs past the end of `u64` fail
+    /// with [`TranslateError::Overflow`].
+    pub fn split(&self, set: &IntervalSet) -> Result<Vec<MapPiece>, TranslateError> {
+        let mut remaining = set.clone();
+        let mut pieces = Vec::new();
+
+        for (source, line) in self.translations() {
+            let source = IntervalSet::from(source.clone());
+            let hit = remaining.intersection(&source);
+
+            if hit.is_empty() {
+                continue;
+            }
+
+            if line.overflows() {
+                return Err(TranslateError::Overflow(
+                    self.source.clone(),
+                    self.destination.clone(),
+                    *line,
+                ));
+            }
+
+            // The line fits in u64, so every value of its source has a destination
+            pieces.extend(hit.ranges().iter().map(|range| MapPiece {
+                source: range.clone(),
+                destination: (line.destination + (range.start - line.source))
+                    ..(line.destination + (range.end - line.source)),
+                line: Some(*line),
+            }));
+
+            remaining = remaining.difference(&source);
+        }
+
+        pieces.extend(remaining.ranges().iter().map(|range| MapPiece {
+            source: range.clone(),
+            destination: range.clone(),
+            line: None,
+        }));
+        pieces.sort_by_key(|piece| piece.source.start);
+
+        Ok(pieces)
+    }
+
+    /// Translate a set of values through the map, see [`CategoryMap::split`]
+    #[inline(always)]
+    pub fn apply(&self, set: &IntervalSet) -> Result<IntervalSet, TranslateError> {
+        Ok(self.split(set)?.into_iter().map(|piece| piece.destination).collect())
+    }
+}
+
+/// A graph of maps between categories
+#[derive(Debug, Default)]
+pub struct TranslationMap(Vec<CategoryMap>);
+
+impl TranslationMap {
+    /// Add a map to the graph
+    #[inline(always)]
+    fn add_map(&mut self, map: CategoryMap) {
+        self.0.push(map);
+    }
+
+    /// Get all maps of the graph
+    #[inline(always)]
+    pub fn maps(&self) -> &[CategoryMap] {
+        &self.0
+    }
+
+    /// Get all categories of the graph
+    pub fn categories(&self) -> Vec<&Category> {
+        let mut categories = self
             .0
             .iter()
-            .filter(|(key, _)| key.typ == from && range.start <= key.range.end && key.range.start <= range.end)
+            .flat_map(|map| [map.source(), map.destination()])
             .collect::<Vec<_>>();
 
-        // Check if next variant exists
-        let Some(next) = from.next_variant() else {
-            // We arrived at Location-variant, so return the beginning of the range
-            return Some(range.start);
-        };
+        categories.sort();
+        categories.dedup();
+
+        categories
+    }
+
+    /// Find the shortest chain of maps from one category to another
+    pub fn path(&self, from: &Category, to: &Category) -> Result<Vec<&CategoryMap>, TranslateError> {
+        // Breadth-first search, remembering which map led to each category
+        let mut previous = BTreeMap::<&Category, Option<&CategoryMap>>::new();
+        let mut queue = VecDeque::from([from]);
 
-        // No keys found, so we just walk the next variant with the same range
-        if keys.is_empty() {
-            return self.walk(next, range);
+        previous.insert(from, None);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = Vec::new();
+                let mut current = category;
+
+                while let Some(Some(map)) = previous.get(current) {
+                    path.push(*map);
+                    current = map.source();
+                }
+
+                path.reverse();
+
+                return Ok(path);
+            }
+
+            self.0.iter().filter(|map| map.source() == category).for_each(|map| {
+                if !previous.contains_key(map.destination()) {
+                    previous.insert(map.destination(), Some(map));
+                    queue.push_back(map.destination());
+                }
+            });
         }
 
-        // For each key, test the lowest value possible first, and keep doing so, until the whole range has been checked.
-        keys.iter()
-            .flat_map(|(key, value)| {
-                // Diff between value and key
-                let diff = value.range.start.abs_diff(key.range.start);
+        Err(TranslateError::NoPath(from.clone(), to.clone()))
+    }
 
-                // Get the range translation
-                let new_range = if key.range.start < value.range.start {
-                    (key.range.start.max(range.start) + diff)..(key.range.end.min(range.end) + diff)
-                } else {
-                    (key.range.start.max(range.start) - diff)..(key.range.end.min(range.end) - diff)
-                };
+    /// Find the category translations from a category end up in
+    ///
+    /// This is the reachable category without any maps of its own, furthest away from the start.
+    /// If every reachable category has a map, e.g. because of a cycle, the start is returned.
+    pub fn terminal(&self, from: &Category) -> Category {
+        let mut visited = vec![from];
+        let mut queue = VecDeque::from([from]);
+        let mut terminal = from;
+
+        while let Some(category) = queue.pop_front() {
+            let mut next = self
+                .0
+                .iter()
+                .filter(|map| map.source() == category)
+                .map(CategoryMap::destination)
+                .peekable();
 
-                // Walk the next variant with the new range
-                self.walk(next, new_range)
-            })
-            // Return the lowest value found
-            .min()
+            if next.peek().is_none() {
+                terminal = category;
+            }
+
+            next.for_each(|destination| {
+                if !visited.contains(&destination) {
+                    visited.push(destination);
+                    queue.push_back(destination);
+                }
+            });
+        }
+
+        terminal.clone()
+    }
+
+    /// Translate a value from one category to another
+    ///
+    /// As ranges can't include `u64::MAX`, it can't be translated.
+    #[inline(always)]
+    pub fn translate(&self, from: &Category, to: &Category, value: u64) -> Result<u64, TranslateError> {
+        let end = value.checked_add(1).ok_or(TranslateError::OutOfDomain(value))?;
+
+        self.translate_range(from, to, value..end)?
+            .ok_or(TranslateError::OutOfDomain(value))
+    }
+
+    /// Translate a range from one category to another, returning the lowest value
+    ///
+    /// Returns `None` for an empty range.
+    #[inline(always)]
+    pub fn translate_range(
+        &self,
+        from: &Category,
+        to: &Category,
+        range: Range<u64>,
+    ) -> Result<Option<u64>, TranslateError> {
+        Ok(self.translate_set(from, to, &range.into())?.min())
+    }
+
+    /// Translate a set of values from one category to another
+    #[inline(always)]
+    pub fn translate_set(
+        &self,
+        from: &Category,
+        to: &Category,
+        set: &IntervalSet,
+    ) -> Result<IntervalSet, TranslateError> {
+        let path = self.path(from, to)?;
+        Self::walk(&path, set)
+    }
+
+    /// Walk the chain of maps
+    #[inline(always)]
+    fn walk(path: &[&CategoryMap], set: &IntervalSet) -> Result<IntervalSet, TranslateError> {
+        path.iter().try_fold(set.clone(), |set, map| map.apply(&set))
     }
 }
 
@@ -173,34 +484,90 @@ impl TranslationMap {
 #[derive(Debug)]
 pub struct Almanac {
     seeds: Vec<u64>,
+    seed_category: Category,
+    location_category: Category,
     translation: TranslationMap,
 }
 
 impl Almanac {
-    /// Get the lowest location of the seed ranges
+    /// The category of the seeds, where translations start
     #[inline(always)]
-    pub fn get_lowest_location_of_seed_ranges(&self) -> Option<u64> {
-        let mut min = u64::MAX;
+    pub const fn seed_category(&self) -> &Category {
+        &self.seed_category
+    }
 
-        for chunk in self.seeds.chunks(2) {
-            let range = chunk[0]..(chunk[0] + chunk[1]);
+    /// The category of the locations, where translations from the seeds end
+    ///
+    /// This is worked out from the maps, see [`TranslationMap::terminal`].
+    #[inline(always)]
+    pub const fn location_category(&self) -> &Category {
+        &self.location_category
+    }
 
-            let result = self.translation.translate_range(ValueType::Seed, range.clone())?;
+    /// Get the graph of maps
+    #[inline(always)]
+    pub const fn translation(&self) -> &TranslationMap {
+        &self.translation
+    }
 
-            if result < min {
-                min = result;
-            };
-        }
+    /// Translate a value between any two connected categories
+    #[inline(always)]
+    pub fn translate(&self, from: &str, to: &str, value: u64) -> Result<u64, TranslateError> {
+        self.translation.translate(&from.into(), &to.into(), value)
+    }
 
-        Some(min)
+    /// Compose the chain of maps between two categories into a single function
+    #[inline(always)]
+    pub fn compose(&self, from: &str, to: &str) -> Result<ComposedMap, TranslateError> {
+        self.translation.compose(&from.into(), &to.into())
     }
 
-    /// Get the lowest location of the seeds
+    /// Get every seed range landing in a band of locations
+    #[inline(always)]
+    pub fn get_seeds_in_locations(&self, band: Range<u64>) -> Result<IntervalSet, TranslateError> {
+        self.translation
+            .reverse_range(&self.seed_category, &self.location_category, band)
+    }
+
+    /// Get the lowest location of the seed ranges, by searching the locations in ascending order
+    #[inline(always)]
+    pub fn search_lowest_location_of_seed_ranges(&self) -> Option<u64> {
+        self.translation
+            .compose(&self.seed_category, &self.location_category)
+            .ok()?
+            .lowest_of(&self.seed_ranges())
+    }
+
+    /// Get the seeds as a set, read in pairs of start and length
+    #[inline(always)]
+    pub fn seed_ranges(&self) -> IntervalSet {
+        self.seeds
+            .chunks(2)
+            .map(|chunk| chunk[0]..chunk[0].saturating_add(*chunk.get(1).unwrap_or(&0)))
+            .collect()
+    }
+
+    /// Get the lowest location of the seed ranges
+    #[inline(always)]
+    pub fn get_lowest_location_of_seed_ranges(&self) -> Option<u64> {
+        self.translation
+            .translate_set(&self.seed_category, &self.location_category, &self.seed_ranges())
+            .ok()?
+            .min()
+    }
+
+    /// Get the lowest location of the seeds, or `None` if any of them can't be translated
     #[inline(always)]
     pub fn get_lowest_location(&self) -> Option<u64> {
         self.seeds
             .iter()
-            .flat_map(|seed| self.translation.get_location_of_seed(*seed))
+            .map(|seed| {
+                self.translation
+                    .translate(&self.seed_category, &self.location_category, *seed)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?
+            .into_iter()
             .min()
     }
 }
@@ -211,13 +578,16 @@ impl FromStr for Almanac {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut lines = s.lines();
 
-        // Seeds are on the first line
-        let seeds = lines
+        // Seeds are on the first line, labeled with their (plural) category
+        let (label, seeds) = lines
             .next()
             .ok_or(ParseAlmanacError::GetSeeds)?
-            .split(':')
-            .last()
-            .ok_or(ParseAlmanacError::GetSeeds)?
+            .split_once(':')
+            .ok_or(ParseAlmanacError::GetSeeds)?;
+
+        let seed_category = Category::new(label.trim().strip_suffix('s').unwrap_or(label.trim()));
+
+        let seeds = seeds
             .split_ascii_whitespace()
             .map(str::parse::<u64>)
             .collect::<Result<Vec<u64>, _>>()?;
@@ -225,7 +595,7 @@ impl FromStr for Almanac {
         // Remove empty line after seeds
         lines.next();
 
-        let mut map = TranslationMap(BTreeMap::new());
+        let mut translation = TranslationMap::default();
 
         let maps = lines
             // Replace newline seperators with pipes, for easy splitting.
@@ -248,17 +618,14 @@ impl FromStr for Almanac {
 
             let key = map_lines.next().ok_or(ParseAlmanacError::NoKeyFound)?;
 
-            let (source, destination) = match key {
-                _ if key.contains("seed-to-soil") => (ValueType::Seed, ValueType::Soil),
-                _ if key.contains("soil-to-fertilizer") => (ValueType::Soil, ValueType::Fertilizer),
-                _ if key.contains("fertilizer-to-water") => (ValueType::Fertilizer, ValueType::Water),
-                _ if key.contains("water-to-light") => (ValueType::Water, ValueType::Light),
-                _ if key.contains("light-to-temperature") => (ValueType::Light, ValueType::Temperature),
-                _ if key.contains("temperature-to-humidity") => (ValueType::Temperature, ValueType::Humidity),
-                _ if key.contains("humidity-to-location") => (ValueType::Humidity, ValueType::Location),
+            // Headers look like `<source>-to-<destination> map:`
+            let (source, destination) = key
+                .strip_suffix(" map:")
+                .and_then(|name| name.split_once("-to-"))
+                .filter(|(source, destination)| !source.is_empty() && !destination.is_empty())
+                .ok_or_else(|| ParseAlmanacError::InvalidMapKey(key.to_string()))?;
 
-                invalid => return Err(ParseAlmanacError::InvalidMapKey(invalid.to_string())),
-            };
+            let mut map = CategoryMap::new(source.into(), destination.into());
 
             let numbers = &mut map_lines.take_while(|s| s.chars().any(|c| c.is_numeric()));
 
@@ -268,49 +635,25 @@ impl FromStr for Almanac {
                     .map(|number| number.parse::<u64>())
                     .collect::<Result<Vec<_>, _>>()?;
 
-                let dest_start = numbers[0];
-                let source_start = numbers[1];
-                let length = numbers[2];
+                let [dest_start, source_start, length] = numbers[..] else {
+                    return Err(ParseAlmanacError::InvalidMapLine(s.to_string()));
+                };
 
-                map.add_translation(
-                    TranslationValue::new(source, source_start, length + source_start),
-                    TranslationValue::new(destination, dest_start, length + dest_start),
-                );
+                map.add_line(MapLine::new(dest_start, source_start, length));
 
                 Ok::<(), ParseAlmanacError>(())
             })?;
 
-            // Fill in the blank spaces
-            let keys = map
-                .0
-                .keys()
-                .filter(|key| key.typ == source)
-                .cloned()
-                .collect::<Vec<_>>();
-
-            let mut from = 0;
-
-            for key in keys {
-                let value = key.range.start;
-
-                if value == 0 {
-                    from = value;
-                    continue;
-                }
-
-                let new_key = TranslationValue::new(source, from, value);
-
-                if !map.0.contains_key(&new_key) {
-                    map.add_translation(new_key, TranslationValue::new(destination, from, value));
-                }
-
-                from = value;
-            }
+            translation.add_map(map);
         }
 
+        let location_category = translation.terminal(&seed_category);
+
         Ok(Self {
             seeds,
-            translation: map,
+            seed_category,
+            location_category,
+            translation,
         })
     }
 }
@@ -369,4 +712,312 @@ humidity-to-location map:
 
         assert_eq!(lowest_location_of_range, 46);
     }
+
+    #[test]
+    fn category_graph() {
+        let almanac = Almanac::from_str(EXAMPLE).expect("Failed to create map");
+
+        assert_eq!(almanac.translate("seed", "soil", 79).expect("No path"), 81);
+        assert_eq!(almanac.translate("soil", "humidity", 81).expect("No path"), 78);
+        assert_eq!(almanac.translate("seed", "location", 79).expect("No path"), 82);
+        assert_eq!(almanac.translate("water", "water", 5).expect("No path"), 5);
+        assert!(matches!(
+            almanac.translate("location", "seed", 82),
+            Err(TranslateError::NoPath(..))
+        ));
+        assert!(almanac.translate("seed", "mineral", 1).is_err());
+        assert_eq!(almanac.translation().categories().len(), 8);
+    }
+
+    #[test]
+    fn custom_categories() {
+        let almanac = Almanac::from_str(
+            "bulbs: 1 5
+
+bulb-to-pot map:
+10 0 3
+
+pot-to-shelf map:
+100 10 2",
+        )
+        .expect("Failed to create map");
+
+        assert_eq!(almanac.seed_category().name(), "bulb");
+        assert_eq!(almanac.location_category().name(), "shelf");
+        assert_eq!(almanac.translate("bulb", "shelf", 1).expect("No path"), 101);
+        assert_eq!(almanac.translate("bulb", "pot", 5).expect("No path"), 5);
+
+        // The seed and location helpers follow the categories of the headers
+        assert_eq!(almanac.get_lowest_location(), Some(5));
+        assert_eq!(almanac.get_lowest_location_of_seed_ranges(), Some(3));
+        assert_eq!(almanac.search_lowest_location_of_seed_ranges(), Some(3));
+        assert_eq!(
+            almanac.get_seeds_in_locations(100..102).expect("No path"),
+            IntervalSet::from_iter([0..2, 10..12, 100..102])
+        );
+        assert_eq!(almanac.explain(1).expect("No path").value(), Some(&101));
+        assert_eq!(
+            almanac.explain_range(1..6).expect("No path").leaves(),
+            IntervalSet::from_iter([3..6, 12..13, 101..102])
+        );
+        assert!(matches!(
+            Almanac::from_str("seeds: 1\n\nnonsense\n1 2 3"),
+            Err(ParseAlmanacError::InvalidMapKey(_))
+        ));
+    }
+
+    #[test]
+    fn composition() {
+        let almanac = Almanac::from_str(EXAMPLE).expect("Failed to create map");
+        let composed = almanac.compose("seed", "location").expect("No path");
+
+        // Segments cover the whole domain without gaps
+        let segments = composed.segments();
+        assert_eq!(segments[0].source().start, 0);
+        assert_eq!(segments[segments.len() - 1].source().end, u64::MAX);
+        assert!(segments.windows(2).all(|w| w[0].source().end == w[1].source().start));
+
+        assert_eq!([79, 14, 55, 13].map(|seed| composed.translate(seed)), [82, 43, 86, 35]);
+        assert_eq!(
+            composed
+                .lowest(79..93)
+                .expect("Empty range")
+                .min(composed.lowest(55..68).expect("Empty range")),
+            46
+        );
+
+        // The exported map gives the same translations
+        let exported = Almanac::from_str(&format!("seeds: 79 14 55 13\n\n{composed}")).expect("Failed to parse export");
+        assert_eq!(exported.get_lowest_location(), Some(35));
+        assert_eq!(exported.get_lowest_location_of_seed_ranges(), Some(46));
+        assert_eq!(exported.compose("seed", "location").expect("No path"), composed);
+    }
+
+    #[test]
+    fn reverse() {
+        let almanac = Almanac::from_str(EXAMPLE).expect("Failed to create map");
+        let translation = almanac.translation();
+        let (seed, location) = (Category::new("seed"), Category::new("location"));
+        let composed = translation.compose(&seed, &location).expect("No path");
+
+        // Every seed landing on a location translates back to it
+        for value in [35, 43, 46, 82, 86] {
+            let seeds = translation.reverse(&seed, &location, value).expect("No path");
+            assert!(!seeds.is_empty());
+
+            for range in seeds.ranges() {
+                assert_eq!(range.end - range.start, 1);
+                assert_eq!(composed.translate(range.start), value);
+            }
+        }
+
+        assert_eq!(
+            translation.reverse(&seed, &location, 35).expect("No path"),
+            IntervalSet::from(13..14)
+        );
+
+        let band = almanac.get_seeds_in_locations(40..50).expect("No path");
+        assert_eq!(band.len(), 10);
+        assert!(band
+            .ranges()
+            .iter()
+            .flat_map(|range| range.clone())
+            .all(|seed| (40..50).contains(&composed.translate(seed))));
+
+        assert_eq!(almanac.search_lowest_location_of_seed_ranges(), Some(46));
+    }
+
+    #[test]
+    fn interval_set() {
+        let a = IntervalSet::from_iter([10..20, 0..5, 4..8, 20..25, 30..30]);
+        assert_eq!(a.ranges(), &[0..8, 10..25]);
+        assert_eq!(a.len(), 23);
+        assert!(a.contains(24) && !a.contains(9));
+
+        let b = IntervalSet::from_iter([5..12, 22..40]);
+        assert_eq!(a.union(&b), IntervalSet::from(0..40));
+        assert_eq!(a.intersection(&b).ranges(), &[5..8, 10..12, 22..25]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 12..22]);
+        assert_eq!(b.difference(&a).ranges(), &[8..10, 25..40]);
+
+        assert_eq!(a.shift(-5).ranges(), &[0..3, 5..20]);
+        assert_eq!(IntervalSet::from(u64::MAX - 2..u64::MAX).shift(5), IntervalSet::new());
+        assert_eq!(a.split_at(&[5, 10, 15]), vec![0..5, 5..8, 10..15, 15..25]);
+    }
+
+    #[test]
+    fn translate_each_seed() {
+        let almanac = Almanac::from_str(EXAMPLE).expect("Failed to create map");
+
+        assert_eq!(
+            [79, 14, 55, 13].map(|seed| almanac.translate("seed", "location", seed).expect("No path")),
+            [82, 43, 86, 35]
+        );
+        assert_eq!(almanac.translate("seed", "soil", 98).expect("No path"), 50);
+        assert_eq!(almanac.translate("seed", "soil", 100).expect("No path"), 100);
+    }
+
+    #[test]
+    fn validate() {
+        let almanac = Almanac::from_str(EXAMPLE).expect("Failed to create map");
+        let report = almanac.validate();
+
+        assert!(report.is_valid());
+        assert_eq!(report.maps().len(), 7);
+
+        // seed-to-soil covers 50..100, fertilizer-to-water covers all of 0..61
+        assert_eq!(report.maps()[0].gaps(), &IntervalSet::from(0..50));
+        assert_eq!((*report.maps()[0].covered(), *report.maps()[0].domain()), (50, 100));
+        assert!(report.maps()[2].gaps().is_empty());
+
+        let almanac = Almanac::from_str(
+            "seeds: 1
+
+seed-to-soil map:
+0 10 5
+20 12 5
+18446744073709551610 30 10
+
+seed-to-soil map:
+0 0 1",
+        )
+        .expect("Failed to create map");
+        let report = almanac.validate();
+
+        assert!(!report.is_valid());
+        assert_eq!(report.maps()[0].overlaps(), &[validation::Overlap::new(0, 1, 12..15)]);
+        assert_eq!(report.maps()[0].overflows(), &[2]);
+        assert_eq!(report.maps()[0].gaps(), &IntervalSet::from_iter([0..10, 17..30]));
+        assert_eq!(*report.maps()[0].covered(), 17);
+        assert_eq!(report.duplicates().len(), 1);
+        assert_eq!(*report.duplicates()[0].count(), 2);
+
+        // An explicit identity line like `0 0 1` counts as coverage, like any other line
+        assert_eq!(*report.maps()[1].covered(), 1);
+        assert_eq!(*report.maps()[1].domain(), 1);
+    }
+
+    #[test]
+    fn explain() {
+        let almanac = Almanac::from_str(EXAMPLE).expect("Failed to create map");
+
+        let path = almanac.explain(79).expect("No path");
+        let values = path.stages().iter().map(|stage| *stage.value()).collect::<Vec<_>>();
+        assert_eq!(values, [79, 81, 81, 81, 74, 78, 78, 82]);
+        assert_eq!(path.stages()[1].line(), &Some(MapLine::new(52, 50, 48)));
+        assert_eq!(path.stages()[2].line(), &None);
+        assert!(path.to_string().contains("identity"));
+
+        let split = almanac.explain_range(79..93).expect("No path");
+        assert_eq!(split.children().len(), 1);
+        assert_eq!(split.children()[0].range(), &(81..95));
+        assert_eq!(
+            split.leaves().min(),
+            almanac
+                .translation()
+                .translate_range(&"seed".into(), &"location".into(), 79..93)
+                .expect("No path")
+        );
+
+        // The leaves are exactly the translated set
+        for range in [79..93, 55..68, 0..100] {
+            let split = almanac.explain_range(range.clone()).expect("No path");
+            let translated = almanac
+                .translation()
+                .translate_set(&"seed".into(), &"location".into(), &range.into())
+                .expect("No path");
+            assert_eq!(split.leaves(), translated);
+        }
+    }
+
+    /// An almanac whose first line runs past the end of u64 for the seeds 15 to 19
+    const OVERFLOWING: &str = "seeds: 17 1
+
+seed-to-soil map:
+18446744073709551610 10 10
+0 50 5";
+
+    #[test]
+    fn overflowing_lines() {
+        let almanac = Almanac::from_str(OVERFLOWING).expect("Failed to create map");
+        let (seed, soil) = (Category::new("seed"), Category::new("soil"));
+        let translation = almanac.translation();
+
+        // Values hitting the overflowing line are errors, not identity
+        assert!(matches!(
+            translation.translate(&seed, &soil, 17),
+            Err(TranslateError::Overflow(_, _, line)) if line == MapLine::new(18446744073709551610, 10, 10)
+        ));
+        assert!(translation.translate_range(&seed, &soil, 0..100).is_err());
+        assert_eq!(almanac.get_lowest_location(), None);
+        assert_eq!(almanac.get_lowest_location_of_seed_ranges(), None);
+
+        // Explaining fails the same way translating does
+        assert!(matches!(
+            translation.explain(&seed, &soil, 17),
+            Err(TranslateError::Overflow(_, _, line)) if line == MapLine::new(18446744073709551610, 10, 10)
+        ));
+        assert!(almanac.explain_range(0..100).is_err());
+        assert!(matches!(
+            translation.explain(&seed, &soil, u64::MAX),
+            Err(TranslateError::OutOfDomain(u64::MAX))
+        ));
+
+        // Values that don't hit it still translate
+        assert_eq!(translation.translate(&seed, &soil, 3).expect("Failed to translate"), 3);
+        assert_eq!(translation.translate(&seed, &soil, 52).expect("Failed to translate"), 2);
+        assert_eq!(
+            translation
+                .explain(&seed, &soil, 52)
+                .expect("Failed to explain")
+                .value(),
+            Some(&2)
+        );
+        assert_eq!(translation.translate_range(&seed, &soil, 3..3).expect("No path"), None);
+        assert!(matches!(
+            translation.translate(&seed, &soil, u64::MAX),
+            Err(TranslateError::OutOfDomain(u64::MAX))
+        ));
+    }
+
+    #[test]
+    fn composition_near_the_end_of_the_domain() {
+        // Composing a chain with an overflowing line fails like translating through it
+        let almanac = Almanac::from_str(OVERFLOWING).expect("Failed to create map");
+        assert!(matches!(
+            almanac.compose("seed", "soil"),
+            Err(TranslateError::Overflow(..))
+        ));
+        assert_eq!(almanac.search_lowest_location_of_seed_ranges(), None);
+
+        // A line ending exactly at the end of u64 fits, and chains into the next map
+        let almanac = Almanac::from_str(
+            "seeds: 10 10
+
+seed-to-soil map:
+18446744073709551605 10 10
+
+soil-to-location map:
+0 18446744073709551610 5",
+        )
+        .expect("Failed to create map");
+        let composed = almanac.compose("seed", "location").expect("Failed to compose");
+
+        let segments = composed.segments();
+        assert_eq!(segments[0].source().start, 0);
+        assert_eq!(segments[segments.len() - 1].source().end, u64::MAX);
+        assert!(segments.windows(2).all(|w| w[0].source().end == w[1].source().start));
+
+        for seed in [0, 9, 10, 12, 14, 15, 17, 19, 20, u64::MAX - 1] {
+            assert_eq!(
+                composed.translate(seed),
+                almanac
+                    .translate("seed", "location", seed)
+                    .expect("Failed to translate")
+            );
+        }
+
+        assert_eq!(composed.translate(17), 2);
+        assert_eq!(almanac.search_lowest_location_of_seed_ranges(), Some(0));
+    }
 }
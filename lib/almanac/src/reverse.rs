@@ -0,0 +1,62 @@
+use crate::{compose::ComposedMap, interval::IntervalSet, Category, TranslateError, TranslationMap};
+use std::ops::Range;
+
+impl ComposedMap {
+    /// Get every source value translating into the given destination range
+    pub fn sources(&self, range: Range<u64>) -> IntervalSet {
+        let range = IntervalSet::from(range);
+
+        self.segments()
+            .iter()
+            .flat_map(|segment| {
+                // Map the overlap back to the source of the segment
+                IntervalSet::from(segment.destination())
+                    .intersection(&range)
+                    .shift(-segment.offset())
+                    .ranges()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    /// Get the lowest destination of any of the source ranges, searching destinations in ascending order
+    pub fn lowest_of(&self, set: &IntervalSet) -> Option<u64> {
+        let mut segments = self.segments().iter().collect::<Vec<_>>();
+        segments.sort_by_key(|segment| segment.destination().start);
+
+        let mut lowest = None;
+
+        for segment in segments {
+            // Segments further along can't go lower than what we already found
+            if lowest.is_some_and(|lowest| segment.destination().start >= lowest) {
+                break;
+            }
+
+            if let Some(start) = set.intersection(&segment.source().clone().into()).min() {
+                let destination = self.translate(start);
+                lowest = Some(lowest.map_or(destination, |lowest: u64| lowest.min(destination)));
+            }
+        }
+
+        lowest
+    }
+}
+
+impl TranslationMap {
+    /// Get every range of the source category translating into the given value of the destination category
+    #[inline(always)]
+    pub fn reverse(&self, from: &Category, to: &Category, value: u64) -> Result<IntervalSet, TranslateError> {
+        self.reverse_range(from, to, value..value.saturating_add(1))
+    }
+
+    /// Get every range of the source category translating into the given range of the destination category
+    #[inline(always)]
+    pub fn reverse_range(
+        &self,
+        from: &Category,
+        to: &Category,
+        range: Range<u64>,
+    ) -> Result<IntervalSet, TranslateError> {
+        Ok(self.compose(from, to)?.sources(range))
+    }
+}
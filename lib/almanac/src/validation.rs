@@ -0,0 +1,310 @@
+use crate::{interval::IntervalSet, Almanac, Category, CategoryMap};
+use std::ops::Range;
+
+/// Two lines of a map whose source ranges overlap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap {
+    first: usize,
+    second: usize,
+    range: Range<u64>,
+}
+
+impl Overlap {
+    /// Create a new overlap
+    #[inline(always)]
+    pub const fn new(first: usize, second: usize, range: Range<u64>) -> Self {
+        Self { first, second, range }
+    }
+
+    /// Get the (zero-based) index of the first line
+    #[inline(always)]
+    pub const fn first(&self) -> &usize {
+        &self.first
+    }
+
+    /// Get the (zero-based) index of the second line
+    #[inline(always)]
+    pub const fn second(&self) -> &usize {
+        &self.second
+    }
+
+    /// Get the overlapping source range
+    #[inline(always)]
+    pub const fn range(&self) -> &Range<u64> {
+        &self.range
+    }
+}
+
+impl std::fmt::Display for Overlap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "lines {} and {} overlap at {}..{}",
+            self.first, self.second, self.range.start, self.range.end
+        )
+    }
+}
+
+/// A validation report for a single map
+///
+/// The domain of a map is everything from `0` up to the end of its highest source range,
+/// as anything beyond it passes through unmapped anyway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapReport {
+    source: Category,
+    destination: Category,
+    overlaps: Vec<Overlap>,
+    overflows: Vec<usize>,
+    gaps: IntervalSet,
+    covered: u64,
+    domain: u64,
+}
+
+impl MapReport {
+    /// Validate a single map
+    pub fn new(map: &CategoryMap) -> Self {
+        let lines = map.lines();
+
+        let overlaps = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(first, a)| {
+                lines.iter().enumerate().skip(first + 1).filter_map(move |(second, b)| {
+                    let overlap = IntervalSet::from(a.source_range()).intersection(&b.source_range().into());
+                    let range = overlap.ranges().first()?.clone();
+                    Some(Overlap::new(first, second, range))
+                })
+            })
+            .collect();
+
+        let overflows = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.overflows())
+            .map(|(index, _)| index)
+            .collect();
+
+        let covered = lines.iter().map(|line| line.source_range()).collect::<IntervalSet>();
+        let domain = covered.ranges().last().map_or(0, |range| range.end);
+
+        Self {
+            source: map.source().clone(),
+            destination: map.destination().clone(),
+            overlaps,
+            overflows,
+            gaps: IntervalSet::from(0..domain).difference(&covered),
+            covered: covered.len(),
+            domain,
+        }
+    }
+
+    /// Get the source category of the map
+    #[inline(always)]
+    pub const fn source(&self) -> &Category {
+        &self.source
+    }
+
+    /// Get the destination category of the map
+    #[inline(always)]
+    pub const fn destination(&self) -> &Category {
+        &self.destination
+    }
+
+    /// Get the lines with overlapping source ranges
+    #[inline(always)]
+    pub fn overlaps(&self) -> &[Overlap] {
+        &self.overlaps
+    }
+
+    /// Get the (zero-based) indices of lines running past the end of `u64`
+    #[inline(always)]
+    pub fn overflows(&self) -> &[usize] {
+        &self.overflows
+    }
+
+    /// Get the parts of the domain passing through unmapped
+    #[inline(always)]
+    pub const fn gaps(&self) -> &IntervalSet {
+        &self.gaps
+    }
+
+    /// Get the number of values explicitly mapped
+    #[inline(always)]
+    pub const fn covered(&self) -> &u64 {
+        &self.covered
+    }
+
+    /// Get the size of the domain
+    #[inline(always)]
+    pub const fn domain(&self) -> &u64 {
+        &self.domain
+    }
+
+    /// Get the fraction of the domain explicitly mapped
+    #[inline(always)]
+    pub fn coverage(&self) -> f64 {
+        if self.domain == 0 {
+            return 1.0;
+        }
+
+        self.covered as f64 / self.domain as f64
+    }
+
+    /// Check if the map has no overlaps or overflows
+    ///
+    /// Gaps are allowed, as the almanac defines them to pass through unchanged.
+    #[inline(always)]
+    pub fn is_valid(&self) -> bool {
+        self.overlaps.is_empty() && self.overflows.is_empty()
+    }
+}
+
+impl std::fmt::Display for MapReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-to-{}: {}/{} covered ({:.1}%)",
+            self.source,
+            self.destination,
+            self.covered,
+            self.domain,
+            self.coverage() * 100.0
+        )?;
+
+        if !self.gaps.is_empty() {
+            write!(f, ", gaps {}", self.gaps)?;
+        }
+
+        for overlap in &self.overlaps {
+            write!(f, ", {overlap}")?;
+        }
+
+        for line in &self.overflows {
+            write!(f, ", line {line} overflows")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// More than one map between the same pair of categories
+///
+/// Only the first of them is ever used for translations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateMap {
+    source: Category,
+    destination: Category,
+    count: usize,
+}
+
+impl DuplicateMap {
+    /// Create a new duplicate map entry
+    #[inline(always)]
+    pub const fn new(source: Category, destination: Category, count: usize) -> Self {
+        Self {
+            source,
+            destination,
+            count,
+        }
+    }
+
+    /// Get the source category of the maps
+    #[inline(always)]
+    pub const fn source(&self) -> &Category {
+        &self.source
+    }
+
+    /// Get the destination category of the maps
+    #[inline(always)]
+    pub const fn destination(&self) -> &Category {
+        &self.destination
+    }
+
+    /// Get the number of maps between the categories
+    #[inline(always)]
+    pub const fn count(&self) -> &usize {
+        &self.count
+    }
+}
+
+impl std::fmt::Display for DuplicateMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-to-{}: {} maps", self.source, self.destination, self.count)
+    }
+}
+
+/// A validation report for a whole almanac
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    maps: Vec<MapReport>,
+    duplicates: Vec<DuplicateMap>,
+}
+
+impl ValidationReport {
+    /// Get the reports of each map, in the order they were written
+    #[inline(always)]
+    pub fn maps(&self) -> &[MapReport] {
+        &self.maps
+    }
+
+    /// Get the pairs of categories with more than one map
+    #[inline(always)]
+    pub fn duplicates(&self) -> &[DuplicateMap] {
+        &self.duplicates
+    }
+
+    /// Check if every map is valid, and there are no duplicate maps
+    #[inline(always)]
+    pub fn is_valid(&self) -> bool {
+        self.duplicates.is_empty() && self.maps.iter().all(MapReport::is_valid)
+    }
+}
+
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .maps
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.duplicates.iter().map(|duplicate| format!("Duplicate {duplicate}")))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Almanac {
+    /// Check the maps for overlapping lines, overflowing lines, gaps and duplicates
+    pub fn validate(&self) -> ValidationReport {
+        let maps = self.translation.maps();
+
+        let mut duplicates = Vec::<DuplicateMap>::new();
+
+        for map in maps {
+            if duplicates
+                .iter()
+                .any(|d| d.source() == map.source() && d.destination() == map.destination())
+            {
+                continue;
+            }
+
+            let count = maps
+                .iter()
+                .filter(|other| other.source() == map.source() && other.destination() == map.destination())
+                .count();
+
+            if count > 1 {
+                duplicates.push(DuplicateMap::new(
+                    map.source().clone(),
+                    map.destination().clone(),
+                    count,
+                ));
+            }
+        }
+
+        ValidationReport {
+            maps: maps.iter().map(MapReport::new).collect(),
+            duplicates,
+        }
+    }
+}
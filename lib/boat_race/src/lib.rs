@@ -103,7 +103,7 @@ impl FromStr for Races {
             .lines()
             .map(|line| {
                 line.split(':')
-                    .last()
+                    .next_back()
                     .ok_or_else(|| ParseRaceError::Invalid("Failed to get numbers".to_string()))?
                     .split_ascii_whitespace()
                     .map(|n| n.parse::<u64>().map_err(ParseRaceError::ParseInt))
@@ -0,0 +1,173 @@
+use crate::{
+    cube::{Color, Cubes},
+    round::{Draw, Round},
+    Game,
+};
+
+/// Natural logarithm of the binomial coefficient `n choose k`
+///
+/// Returns negative infinity when the coefficient is zero.
+#[inline(always)]
+fn ln_choose(n: i32, k: i32) -> f64 {
+    if k < 0 || k > n {
+        return f64::NEG_INFINITY;
+    }
+
+    // Use the smaller side for fewer terms
+    let k = k.min(n - k);
+
+    (1..=k).map(|i| (f64::from(n - k + i) / f64::from(i)).ln()).sum()
+}
+
+/// Sums values in log-space, without underflowing
+#[inline(always)]
+fn ln_sum_exp(values: impl Iterator<Item = f64>) -> f64 {
+    let values = values.collect::<Vec<_>>();
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+
+    max + values.iter().map(|value| (value - max).exp()).sum::<f64>().ln()
+}
+
+/// Get all ways of splitting `total` into `parts` non-negative counts
+fn compositions(total: i32, parts: usize) -> Vec<Vec<i32>> {
+    match parts {
+        0 if total == 0 => vec![vec![]],
+        0 => vec![],
+        1 => vec![vec![total]],
+        _ => (0..=total)
+            .flat_map(|first| {
+                compositions(total - first, parts - 1).into_iter().map(move |mut rest| {
+                    rest.insert(0, first);
+                    rest
+                })
+            })
+            .collect(),
+    }
+}
+
+impl Draw {
+    /// Get the log-likelihood of the draw from a bag, sampling without replacement
+    ///
+    /// This is the multivariate hypergeometric distribution, where every color of the bag
+    /// that isn't part of the draw was drawn zero times.
+    pub fn log_likelihood(&self, bag: &Cubes) -> f64 {
+        let bag_size = bag.values().sum::<i32>();
+        let draw_size = self.cubes().values().sum::<i32>();
+
+        let drawn = self
+            .cubes()
+            .iter()
+            .map(|(color, count)| ln_choose(*bag.get(color).unwrap_or(&0), *count))
+            .sum::<f64>();
+
+        // Impossible draws would otherwise give `-inf - -inf`, which is NaN
+        if draw_size > bag_size || drawn == f64::NEG_INFINITY {
+            return f64::NEG_INFINITY;
+        }
+
+        drawn - ln_choose(bag_size, draw_size)
+    }
+}
+
+impl Round {
+    /// Get the log-likelihood of all draws of the round from a bag
+    ///
+    /// Cubes are put back into the bag between draws, so the draws are independent.
+    pub fn log_likelihood(&self, bag: &Cubes) -> f64 {
+        self.draws().iter().map(|draw| draw.log_likelihood(bag)).sum()
+    }
+
+    /// Get the likelihood of all draws of the round from a bag
+    #[inline(always)]
+    pub fn likelihood(&self, bag: &Cubes) -> f64 {
+        self.log_likelihood(bag).exp()
+    }
+}
+
+/// A prior distribution over possible bag compositions
+#[derive(Debug, Clone)]
+pub struct BagPrior(Vec<(Cubes, f64)>);
+
+impl BagPrior {
+    /// Create a uniform prior over every composition of `total` cubes across the colors
+    ///
+    /// The amount of compositions grows quickly with the amount of colors, so keep the palette small.
+    pub fn uniform(total: i32, colors: &[Color]) -> Self {
+        compositions(total, colors.len())
+            .into_iter()
+            .map(|counts| (colors.iter().cloned().zip(counts).collect(), 1.0))
+            .collect()
+    }
+
+    /// Create a prior from weighted bag compositions
+    ///
+    /// Weights don't need to sum to one, as they are normalized.
+    #[inline(always)]
+    pub fn from_weights(weights: Vec<(Cubes, f64)>) -> Self {
+        Self(weights)
+    }
+
+    /// Get the bag compositions with their normalized log-weights, skipping impossible bags
+    fn log_weights(&self) -> impl Iterator<Item = (&Cubes, f64)> {
+        let total = self.0.iter().map(|(_, weight)| weight).sum::<f64>().ln();
+
+        self.0
+            .iter()
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(move |(bag, weight)| (bag, weight.ln() - total))
+    }
+
+    /// Get the marginal likelihood of a round's draws under the prior
+    pub fn likelihood(&self, round: &Round) -> f64 {
+        ln_sum_exp(
+            self.log_weights()
+                .map(|(bag, weight)| weight + round.log_likelihood(bag)),
+        )
+        .exp()
+    }
+}
+
+impl FromIterator<(Cubes, f64)> for BagPrior {
+    #[inline(always)]
+    fn from_iter<T: IntoIterator<Item = (Cubes, f64)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Game {
+    /// Get the likelihood of each round's draws under the prior, by round ID
+    pub fn get_likelihoods(&self, prior: &BagPrior) -> Vec<(i32, f64)> {
+        self.rounds
+            .iter()
+            .map(|round| (*round.id(), prior.likelihood(round)))
+            .collect()
+    }
+
+    /// Get the most likely bag composition given every round of the game, with its posterior probability
+    ///
+    /// Returns `None` if no bag of the prior could have produced the draws.
+    pub fn get_most_likely_bag(&self, prior: &BagPrior) -> Option<(Cubes, f64)> {
+        let posterior = prior
+            .log_weights()
+            .map(|(bag, weight)| {
+                let likelihood = self.rounds.iter().map(|round| round.log_likelihood(bag)).sum::<f64>();
+                (bag, weight + likelihood)
+            })
+            .collect::<Vec<_>>();
+
+        let evidence = ln_sum_exp(posterior.iter().map(|(_, value)| *value));
+
+        if evidence == f64::NEG_INFINITY {
+            return None;
+        }
+
+        posterior
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(bag, value)| (bag.clone(), (value - evidence).exp()))
+    }
+}
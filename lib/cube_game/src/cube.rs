@@ -2,10 +2,11 @@ use std::{
     collections::HashMap,
     ops::{Deref, DerefMut},
     str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, OnceLock, Weak},
 };
 
 /// A set of Cubes.
-#[derive(Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Cubes(HashMap<Color, i32>);
 
 impl Cubes {
@@ -25,6 +26,13 @@ impl Cubes {
     }
 }
 
+impl FromIterator<(Color, i32)> for Cubes {
+    #[inline(always)]
+    fn from_iter<T: IntoIterator<Item = (Color, i32)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 // Deref traits for Cubes so we can use hashmap methods.
 impl Deref for Cubes {
     type Target = HashMap<Color, i32>;
@@ -43,7 +51,7 @@ impl DerefMut for Cubes {
 
 /// Color parsing error
 #[derive(Debug)]
-pub struct ColorError(String);
+pub struct ColorError(pub(crate) String);
 
 impl std::fmt::Display for ColorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -53,24 +61,194 @@ impl std::fmt::Display for ColorError {
 
 impl std::error::Error for ColorError {}
 
+/// A registry of interned color names
+///
+/// Colors share their names through reference counting, while the registry only keeps weak
+/// references to them. A name is freed once no color uses it any more, so any text can be parsed
+/// without permanently growing the registry. `red`, `green` and `blue` are built in, and never
+/// take up space in the registry.
+#[derive(Debug, Default)]
+pub struct ColorRegistry {
+    names: HashMap<Box<str>, Weak<str>>,
+}
+
+impl ColorRegistry {
+    /// Get the global color registry
+    #[inline(always)]
+    fn global() -> &'static Mutex<ColorRegistry> {
+        static REGISTRY: OnceLock<Mutex<ColorRegistry>> = OnceLock::new();
+        REGISTRY.get_or_init(Mutex::default)
+    }
+
+    /// Lock the global color registry
+    #[inline(always)]
+    fn lock() -> MutexGuard<'static, ColorRegistry> {
+        Self::global().lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Intern a color name, registering it if no color uses it yet
+    ///
+    /// Names are case-insensitive, and stored in lowercase.
+    pub fn intern(name: &str) -> Color {
+        let name = name.to_lowercase();
+
+        if let Some(color) = Color::builtin(&name) {
+            return color;
+        }
+
+        let mut registry = Self::lock();
+
+        if let Some(shared) = registry.names.get(name.as_str()).and_then(Weak::upgrade) {
+            return Color(Name::Interned(shared));
+        }
+
+        // Drop the names of colors that no longer exist, before adding a new one
+        registry.names.retain(|_, shared| shared.strong_count() > 0);
+
+        let shared = Arc::<str>::from(name.as_str());
+        registry.names.insert(name.into_boxed_str(), Arc::downgrade(&shared));
+
+        Color(Name::Interned(shared))
+    }
+
+    /// Look up a color by name, ignoring case, if it's built in or still in use
+    pub fn lookup(name: &str) -> Option<Color> {
+        let name = name.to_lowercase();
+
+        Color::builtin(&name).or_else(|| {
+            Self::lock()
+                .names
+                .get(name.as_str())
+                .and_then(Weak::upgrade)
+                .map(|shared| Color(Name::Interned(shared)))
+        })
+    }
+
+    /// Get all colors that are built in or still in use, in order
+    pub fn colors() -> Vec<Color> {
+        let mut colors = Self::lock()
+            .names
+            .values()
+            .filter_map(Weak::upgrade)
+            .map(|shared| Color(Name::Interned(shared)))
+            .collect::<Vec<_>>();
+
+        colors.extend(Color::BUILTIN);
+        colors.sort();
+
+        colors
+    }
+}
+
+/// The name of a color, either built in or shared through the [`ColorRegistry`]
+#[derive(Clone)]
+enum Name {
+    Builtin(&'static str),
+    Interned(Arc<str>),
+}
+
 /// A color of a cube
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-pub enum Color {
-    Red,
-    Green,
-    Blue,
+///
+/// Colors are interned names in the [`ColorRegistry`], so any palette can be used.
+/// Colors are ordered with the built in colors first, followed by the rest by name.
+#[derive(Clone)]
+pub struct Color(Name);
+
+impl Color {
+    /// The color red
+    pub const RED: Self = Self(Name::Builtin("red"));
+
+    /// The color green
+    pub const GREEN: Self = Self(Name::Builtin("green"));
+
+    /// The color blue
+    pub const BLUE: Self = Self(Name::Builtin("blue"));
+
+    /// The built in colors
+    pub const BUILTIN: [Self; 3] = [Self::RED, Self::GREEN, Self::BLUE];
+
+    /// Create a color from a name, interning it if needed
+    #[inline(always)]
+    pub fn new(name: &str) -> Self {
+        ColorRegistry::intern(name)
+    }
+
+    /// Get the built in color with a (lowercase) name
+    #[inline(always)]
+    pub fn builtin(name: &str) -> Option<Self> {
+        Self::BUILTIN.into_iter().find(|color| color.name() == name)
+    }
+
+    /// Get the name of the color
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        match &self.0 {
+            Name::Builtin(name) => name,
+            Name::Interned(name) => name,
+        }
+    }
+
+    /// Get the position of the color in the built in colors, placing others after them
+    #[inline(always)]
+    fn rank(&self) -> usize {
+        Self::BUILTIN
+            .iter()
+            .position(|color| color.name() == self.name())
+            .unwrap_or(Self::BUILTIN.len())
+    }
+}
+
+impl PartialEq for Color {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Color {}
+
+impl std::hash::Hash for Color {
+    #[inline(always)]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+    }
+}
+
+impl PartialOrd for Color {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Color {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.rank(), self.name()).cmp(&(other.rank(), other.name()))
+    }
+}
+
+impl std::fmt::Debug for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Color").field(&self.name()).finish()
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 impl FromStr for Color {
     type Err = ColorError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "red" => Ok(Self::Red),
-            "green" => Ok(Self::Green),
-            "blue" => Ok(Self::Blue),
-            // Invalid color, so we return an error
-            s => Err(ColorError(s.to_string())),
+        // Color names have to be a single alphabetic word
+        if s.is_empty() || !s.chars().all(char::is_alphabetic) {
+            return Err(ColorError(s.to_string()));
         }
+
+        Ok(Self::new(s))
     }
 }
@@ -1,10 +1,16 @@
 use std::str::FromStr;
 
+pub mod analysis;
 pub mod cube;
+mod parser;
+pub mod query;
 pub mod round;
+pub mod stats;
+pub mod validation;
 
 pub use cube::Cubes;
 use round::{Round, RoundError};
+use validation::{ValidationReport, Violation};
 
 /// An error describing something wrong with the game
 #[derive(Debug)]
@@ -25,20 +31,66 @@ impl std::fmt::Display for GameError {
 }
 
 /// A game of "cube conundrum"
+#[derive(Debug, Clone, PartialEq)]
 pub struct Game {
     rounds: Vec<Round>,
     limits: Cubes,
 }
 
 impl Game {
+    /// Creates a builder for a game
+    #[inline(always)]
+    pub fn builder() -> GameBuilder {
+        GameBuilder::default()
+    }
+
+    /// Turns the game back into a builder, for editing
+    #[inline(always)]
+    pub fn into_builder(self) -> GameBuilder {
+        GameBuilder {
+            rounds: self.rounds,
+            limits: self.limits,
+        }
+    }
+
     /// Creates a new game
+    ///
+    /// Blank lines are skipped, and both LF and CRLF line endings are accepted.
     #[inline(always)]
     pub fn new(string: &str, limits: Cubes) -> Result<Self, GameError> {
-        let rounds = string.split('\n').map(Round::from_str).collect::<Result<Vec<_>, _>>()?;
+        let rounds = string
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Round::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self { rounds, limits })
     }
 
+    /// Creates a new game in strict mode, rejecting any colors that aren't listed in the limits
+    #[inline(always)]
+    pub fn new_strict(string: &str, limits: Cubes) -> Result<Self, GameError> {
+        let game = Self::new(string, limits)?;
+
+        game.rounds
+            .iter()
+            .try_for_each(|round| round.check_colors(&game.limits))?;
+
+        Ok(game)
+    }
+
+    /// Returns the rounds of the game
+    #[inline(always)]
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    /// Returns the limits of the game
+    #[inline(always)]
+    pub const fn limits(&self) -> &Cubes {
+        &self.limits
+    }
+
     /// Returns the sum of all valid round IDs
     #[inline(always)]
     pub fn get_ids_sum(&self) -> i32 {
@@ -53,6 +105,21 @@ impl Game {
             .sum()
     }
 
+    /// Returns a validation report for each round
+    #[inline(always)]
+    pub fn validate(&self) -> Vec<ValidationReport> {
+        self.rounds.iter().map(|round| round.validate(&self.limits)).collect()
+    }
+
+    /// Returns all violations across every round
+    #[inline(always)]
+    pub fn get_violations(&self) -> Vec<Violation> {
+        self.validate()
+            .into_iter()
+            .flat_map(|report| report.violations().to_vec())
+            .collect()
+    }
+
     /// Returns the sum of the minimum power of all rounds
     #[inline(always)]
     pub fn get_minimum_powers_sum(&self) -> i32 {
@@ -60,9 +127,57 @@ impl Game {
     }
 }
 
+/// Writes the game in the puzzle format, one round per line
+///
+/// Parsing the output with [`Game::new`] gives back an equal game.
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rounds = self.rounds.iter().map(Round::to_string).collect::<Vec<_>>();
+        write!(f, "{}", rounds.join("\n"))
+    }
+}
+
+/// A builder for a [`Game`]
+#[derive(Debug, Default, Clone)]
+pub struct GameBuilder {
+    rounds: Vec<Round>,
+    limits: Cubes,
+}
+
+impl GameBuilder {
+    /// Set the limits of the game
+    #[inline(always)]
+    pub fn limits(mut self, limits: Cubes) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Add a round to the game
+    #[inline(always)]
+    pub fn round(mut self, round: Round) -> Self {
+        self.rounds.push(round);
+        self
+    }
+
+    /// Get the rounds added so far, for editing
+    #[inline(always)]
+    pub fn rounds_mut(&mut self) -> &mut Vec<Round> {
+        &mut self.rounds
+    }
+
+    /// Build the game
+    #[inline(always)]
+    pub fn build(self) -> Game {
+        Game {
+            rounds: self.rounds,
+            limits: self.limits,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use cube::Color;
+    use cube::{Color, ColorRegistry};
 
     use super::*;
 
@@ -75,9 +190,9 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
     #[test]
     fn test_solution_1() {
         let mut limits = Cubes::new();
-        limits.insert(Color::Red, 12);
-        limits.insert(Color::Green, 13);
-        limits.insert(Color::Blue, 14);
+        limits.insert(Color::RED, 12);
+        limits.insert(Color::GREEN, 13);
+        limits.insert(Color::BLUE, 14);
 
         let game = Game::new(GAME, limits).expect("Failed to create game");
         assert_eq!(game.get_ids_sum(), 8);
@@ -90,4 +205,333 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
         assert_eq!(game.get_minimum_powers_sum(), 2286)
     }
+
+    #[test]
+    fn test_custom_colors() {
+        let game = "Game 1: 3 yellow, 4 purple; 5 yellow
+Game 2: 7 purple, 1 yellow";
+
+        let mut limits = Cubes::new();
+        limits.insert(Color::new("yellow"), 5);
+        limits.insert(Color::new("purple"), 6);
+
+        let game = Game::new(game, limits).expect("Failed to create game");
+        assert_eq!(game.get_ids_sum(), 1);
+        assert_eq!(game.get_minimum_powers_sum(), 20 + 7);
+    }
+
+    #[test]
+    fn test_strict_colors() {
+        let mut limits = Cubes::new();
+        limits.insert(Color::RED, 12);
+        limits.insert(Color::GREEN, 13);
+
+        assert!(Game::new_strict(GAME, limits).is_err());
+
+        let mut limits = Cubes::new();
+        limits.insert(Color::RED, 12);
+        limits.insert(Color::GREEN, 13);
+        limits.insert(Color::BLUE, 14);
+
+        assert!(Game::new_strict(GAME, limits).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_colors() {
+        let mut limits = Cubes::new();
+        limits.insert(Color::RED, 12);
+
+        // Any palette is accepted by default, but strict mode only accepts the colors of the limits
+        let game = Game::new("Game 1: 3 ochre, 1 red", limits.clone()).expect("Failed to create game");
+        assert_eq!(game.get_ids_sum(), 1);
+        assert!(matches!(
+            Game::new_strict("Game 1: 3 ochre, 1 red", limits),
+            Err(GameError(RoundError::ParseColor(_)))
+        ));
+
+        // Names are only kept while a color uses them
+        assert_eq!(ColorRegistry::lookup("ochre"), Some(Color::new("ochre")));
+        drop(game);
+        assert!(ColorRegistry::lookup("ochre").is_none());
+    }
+
+    #[test]
+    fn test_violations() {
+        let mut limits = Cubes::new();
+        limits.insert(Color::RED, 12);
+        limits.insert(Color::GREEN, 13);
+        limits.insert(Color::BLUE, 14);
+
+        let game = Game::new(GAME, limits).expect("Failed to create game");
+
+        let reports = game.validate();
+        assert_eq!(
+            reports
+                .iter()
+                .filter(|r| !r.is_valid())
+                .map(|r| *r.round_id())
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+
+        let violations = game.get_violations();
+        assert_eq!(
+            violations,
+            vec![
+                Violation::new(3, 0, Color::RED, 20, 12),
+                Violation::new(4, 2, Color::RED, 14, 12),
+                Violation::new(4, 2, Color::BLUE, 15, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_likelihoods() {
+        use analysis::BagPrior;
+
+        let mut bag = Cubes::new();
+        bag.insert(Color::RED, 2);
+        bag.insert(Color::BLUE, 2);
+
+        let round = Round::from_str("Game 1: 1 red, 1 blue").expect("Failed to parse round");
+        assert!((round.likelihood(&bag) - 4.0 / 6.0).abs() < 1e-9);
+
+        let round = Round::from_str("Game 2: 3 red").expect("Failed to parse round");
+        assert_eq!(round.likelihood(&bag), 0.0);
+
+        let game = Game::new("Game 1: 2 red; 2 red", Cubes::new()).expect("Failed to create game");
+        let prior = BagPrior::uniform(3, &[Color::RED, Color::BLUE]);
+
+        let (bag, probability) = game.get_most_likely_bag(&prior).expect("No bag found");
+        assert_eq!(bag.get(&Color::RED), Some(&3));
+        assert_eq!(bag.get(&Color::BLUE), Some(&0));
+        assert!((probability - 0.9).abs() < 1e-9);
+
+        let likelihoods = game.get_likelihoods(&prior);
+        assert_eq!(likelihoods.len(), 1);
+        assert!((likelihoods[0].1 - (1.0 + 1.0 / 9.0) / 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_likelihoods_mixed_sizes() {
+        use analysis::BagPrior;
+
+        // A draw larger than the bag is impossible, not NaN
+        let mut bag = Cubes::new();
+        bag.insert(Color::RED, 2);
+        bag.insert(Color::BLUE, 2);
+
+        let round = Round::from_str("Game 1: 5 red").expect("Failed to parse round");
+        assert_eq!(round.likelihood(&bag), 0.0);
+
+        let small = Cubes::from_iter([(Color::RED, 2)]);
+        let large = Cubes::from_iter([(Color::RED, 6)]);
+        let prior = BagPrior::from_weights(vec![(small, 1.0), (large.clone(), 1.0)]);
+
+        let game = Game::new("Game 1: 5 red", Cubes::new()).expect("Failed to create game");
+
+        let likelihoods = game.get_likelihoods(&prior);
+        assert!((likelihoods[0].1 - 0.5).abs() < 1e-9);
+
+        let (bag, probability) = game.get_most_likely_bag(&prior).expect("No bag found");
+        assert_eq!(bag, large);
+        assert!((probability - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let game = Game::new(GAME, Cubes::new()).expect("Failed to create game");
+        assert_eq!(game.to_string(), GAME);
+
+        let reparsed = Game::new(&game.to_string(), Cubes::new()).expect("Failed to reparse game");
+        assert_eq!(reparsed, game);
+    }
+
+    #[test]
+    fn test_builders() {
+        let draw = round::Draw::builder()
+            .cube(Color::BLUE, 3)
+            .cube(Color::RED, 4)
+            .build()
+            .expect("Failed to build draw");
+        let round = Round::builder(1)
+            .draw(draw)
+            .draw(
+                round::Draw::builder()
+                    .cube(Color::GREEN, 2)
+                    .build()
+                    .expect("Failed to build draw"),
+            )
+            .build()
+            .expect("Failed to build round");
+        let game = Game::builder().round(round.clone()).build();
+
+        assert_eq!(game.to_string(), "Game 1: 3 blue, 4 red; 2 green");
+        assert_eq!(
+            Game::new(&game.to_string(), Cubes::new()).expect("Failed to parse game"),
+            game
+        );
+
+        let edited = round.into_builder().id(7).build().expect("Failed to build round");
+        assert_eq!(edited.to_string(), "Game 7: 3 blue, 4 red; 2 green");
+
+        assert!(round::Draw::builder()
+            .cube(Color::RED, 1)
+            .cube(Color::RED, 2)
+            .build()
+            .is_err());
+        assert!(Round::builder(1).build().is_err());
+    }
+
+    #[test]
+    fn test_builders_reject_unprintable() {
+        // Names are normalized, so they print the way they parse
+        let dark = Color::new("Dark");
+        assert_eq!(dark.name(), "dark");
+        assert_eq!(Color::new("DARK"), dark);
+
+        let round = Round::builder(1)
+            .draw(
+                round::Draw::builder()
+                    .cube(dark, 1)
+                    .build()
+                    .expect("Failed to build draw"),
+            )
+            .build()
+            .expect("Failed to build round");
+        assert_eq!(
+            Round::from_str(&round.to_string()).expect("Failed to reparse round"),
+            round
+        );
+
+        assert!(matches!(
+            round::Draw::builder().cube(Color::RED, -1).build(),
+            Err(RoundError::NegativeCount(_, -1))
+        ));
+        assert!(matches!(
+            round::Draw::builder().cube(Color::new("dark blue"), 1).build(),
+            Err(RoundError::UnprintableColor(_))
+        ));
+        assert!(matches!(
+            round::Draw::builder().cube(Color::new("reds"), 1).build(),
+            Err(RoundError::UnprintableColor(_))
+        ));
+
+        let draw = round::Draw::builder()
+            .cube(Color::RED, 1)
+            .build()
+            .expect("Failed to build draw");
+        assert!(matches!(
+            Round::builder(-1).draw(draw).build(),
+            Err(RoundError::NegativeId(-1))
+        ));
+    }
+
+    #[test]
+    fn test_round_trip_independent_of_other_colors() {
+        let draw = round::Draw::builder()
+            .cube(Color::new("greys"), 1)
+            .build()
+            .expect("Failed to build draw");
+        let round = Round::builder(1).draw(draw).build().expect("Failed to build round");
+
+        // Creating the singular color afterwards doesn't change how the plural is read
+        let grey = Color::new("grey");
+        assert_eq!(round.to_string(), "Game 1: 1 greys");
+        assert_eq!(
+            Round::from_str(&round.to_string()).expect("Failed to reparse round"),
+            round
+        );
+        assert!(round::Draw::is_printable(&grey));
+    }
+
+    #[test]
+    fn test_lenient_input() {
+        let input = "\r\nGame 1: 3 Blues, 4 red; 1 RED, 2 greens\r\n\r\nGame 2: 1 blue\r\n";
+
+        let game = Game::new(input, Cubes::new()).expect("Failed to create game");
+        assert_eq!(
+            game.to_string(),
+            "Game 1: 3 blue, 4 red; 1 red, 2 green\nGame 2: 1 blue"
+        );
+    }
+
+    #[test]
+    fn test_malformed_draws() {
+        assert!(matches!(
+            Round::from_str("Game 1: 3 blue, 4"),
+            Err(RoundError::MissingColor(4))
+        ));
+        assert!(matches!(
+            Round::from_str("Game 1: blue"),
+            Err(RoundError::MissingCount(_))
+        ));
+        assert!(matches!(
+            Round::from_str("Game 1: 3 blue,; 2 red"),
+            Err(RoundError::TrailingComma)
+        ));
+        assert!(matches!(
+            Round::from_str("Game 1: 3 blue! 2 red"),
+            Err(RoundError::InvalidCharacter('!'))
+        ));
+        assert!(matches!(
+            Round::from_str("Game 1 3 blue"),
+            Err(RoundError::UnexpectedToken(_))
+        ));
+        assert!(matches!(Round::from_str("Game: 3 blue"), Err(RoundError::NoId)));
+        assert!(matches!(
+            Round::from_str("Game 1: 3 blue, 2 Blues"),
+            Err(RoundError::DuplicateCubes(color)) if color == Color::BLUE
+        ));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let game = Game::new(GAME, Cubes::new()).expect("Failed to create game");
+        let stats = game.get_statistics();
+
+        assert_eq!(*stats.draws(), 14);
+        assert_eq!(stats.total(&Color::RED), 4 + 1 + 1 + 20 + 4 + 1 + 3 + 6 + 14 + 6 + 1);
+        assert!((stats.mean(&Color::BLUE) - 50.0 / 14.0).abs() < 1e-9);
+        assert_eq!(stats.max(&Color::RED), 20);
+        assert_eq!(stats.max(&Color::GREEN), 13);
+        assert_eq!(stats.max(&Color::BLUE), 15);
+        assert_eq!(
+            stats
+                .draws_per_round()
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .collect::<Vec<_>>(),
+            vec![(2, 1), (3, 4)]
+        );
+
+        let mut limits = Cubes::new();
+        limits.insert(Color::RED, 12);
+        limits.insert(Color::GREEN, 13);
+        limits.insert(Color::BLUE, 14);
+
+        let mut loose = limits.clone();
+        loose.insert(Color::RED, 20);
+
+        assert_eq!(
+            game.get_feasible_ids(&[limits, loose]),
+            vec![vec![1, 2, 5], vec![1, 2, 3, 5]]
+        );
+    }
+
+    #[test]
+    fn test_query() {
+        use query::Measure;
+
+        let game = Game::new(GAME, Cubes::new()).expect("Failed to create game");
+
+        let query = Measure::max(Color::RED).gt(10).and(Measure::draws().lt(4));
+        assert_eq!(game.query(&query), vec![3, 4]);
+
+        let query = Measure::draws().eq(2).or(Measure::total(Color::GREEN).ge(20));
+        assert_eq!(game.query(&query), vec![3, 5]);
+
+        let query = !Measure::max(Color::BLUE).le(6);
+        assert_eq!(game.query(&query), vec![4]);
+    }
 }
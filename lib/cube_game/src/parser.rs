@@ -0,0 +1,200 @@
+use crate::{
+    cube::Color,
+    round::{Draw, Round, RoundError},
+};
+use std::{iter::Peekable, str::FromStr};
+
+/// A token of the game grammar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Number(&'a str),
+    Word(&'a str),
+    Colon,
+    Semicolon,
+    Comma,
+}
+
+impl std::fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(s) | Self::Word(s) => write!(f, "{s}"),
+            Self::Colon => write!(f, ":"),
+            Self::Semicolon => write!(f, ";"),
+            Self::Comma => write!(f, ","),
+        }
+    }
+}
+
+/// Splits a line of the game grammar into tokens, skipping any whitespace
+pub(crate) struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Create a new tokenizer for the input
+    #[inline(always)]
+    pub(crate) const fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Take characters from the current position while they match the predicate
+    #[inline(always)]
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.input[self.position..];
+        let length = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.position += length;
+        &rest[..length]
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, RoundError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip whitespace, which also takes care of any carriage returns
+        self.take_while(char::is_whitespace);
+
+        let c = self.input[self.position..].chars().next()?;
+
+        let token = match c {
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            _ if c.is_ascii_digit() => return Some(Ok(Token::Number(self.take_while(|c| c.is_ascii_digit())))),
+            _ if c.is_alphabetic() => return Some(Ok(Token::Word(self.take_while(char::is_alphabetic)))),
+            _ => return Some(Err(RoundError::InvalidCharacter(c))),
+        };
+
+        self.position += c.len_utf8();
+
+        Some(Ok(token))
+    }
+}
+
+/// A parser for the game grammar
+///
+/// ```text
+/// round := "Game" NUMBER ":" draw (";" draw)*
+/// draw  := (NUMBER COLOR ("," NUMBER COLOR)*)?
+/// ```
+///
+/// Color names are case-insensitive, and a plural `s` is dropped from the built in colors, e.g. `Blues`.
+/// Other colors are taken as written, so reading a name never depends on which colors exist.
+pub(crate) struct Parser<'a> {
+    tokens: Peekable<Tokenizer<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    /// Create a new parser for the input
+    #[inline(always)]
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self {
+            tokens: Tokenizer::new(input).peekable(),
+        }
+    }
+
+    /// Get the next token
+    #[inline(always)]
+    fn next(&mut self) -> Result<Option<Token<'a>>, RoundError> {
+        self.tokens.next().transpose()
+    }
+
+    /// Peek at the next token
+    #[inline(always)]
+    fn peek(&mut self) -> Result<Option<Token<'a>>, RoundError> {
+        match self.tokens.peek() {
+            Some(Ok(token)) => Ok(Some(*token)),
+            // Consume the error, so it can be returned by value
+            Some(Err(_)) => self.next(),
+            None => Ok(None),
+        }
+    }
+
+    /// Fail if there are any tokens left
+    #[inline(always)]
+    pub(crate) fn finish(mut self) -> Result<(), RoundError> {
+        match self.next()? {
+            Some(token) => Err(RoundError::UnexpectedToken(token.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse a whole round
+    pub(crate) fn round(&mut self) -> Result<Round, RoundError> {
+        match self.next()? {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("game") => (),
+            Some(token) => return Err(RoundError::UnexpectedToken(token.to_string())),
+            None => return Err(RoundError::NoId),
+        }
+
+        let id = match self.next()? {
+            Some(Token::Number(number)) => number.parse::<i32>()?,
+            _ => return Err(RoundError::NoId),
+        };
+
+        match self.next()? {
+            Some(Token::Colon) => (),
+            Some(token) => return Err(RoundError::UnexpectedToken(token.to_string())),
+            None => return Err(RoundError::NoDraws),
+        }
+
+        let mut round = Round::builder(id).draw(self.draw()?);
+
+        while let Some(token) = self.next()? {
+            if token != Token::Semicolon {
+                return Err(RoundError::UnexpectedToken(token.to_string()));
+            }
+
+            round = round.draw(self.draw()?);
+        }
+
+        round.build()
+    }
+
+    /// Parse a single draw, stopping before a `;` or the end of the input
+    pub(crate) fn draw(&mut self) -> Result<Draw, RoundError> {
+        let mut draw = Draw::builder();
+
+        loop {
+            let count = match self.peek()? {
+                Some(Token::Number(number)) => number.parse::<i32>()?,
+                Some(Token::Word(word)) => return Err(RoundError::MissingCount(word.to_string())),
+                // An empty draw, or the end of the draw
+                Some(Token::Semicolon) | None => break,
+                Some(token) => return Err(RoundError::UnexpectedToken(token.to_string())),
+            };
+            self.next()?;
+
+            let color = match self.next()? {
+                Some(Token::Word(word)) => Self::color(word)?,
+                _ => return Err(RoundError::MissingColor(count)),
+            };
+
+            draw = draw.cube(color, count);
+
+            // Cubes are seperated by commas, but be lenient about leaving them out
+            if self.peek()? == Some(Token::Comma) {
+                self.next()?;
+
+                if !matches!(self.peek()?, Some(Token::Number(_))) {
+                    return Err(RoundError::TrailingComma);
+                }
+            }
+        }
+
+        draw.build()
+    }
+
+    /// Parse a color name, ignoring case and the plural `s` of a built in color
+    #[inline(always)]
+    fn color(word: &str) -> Result<Color, RoundError> {
+        let name = word.to_lowercase();
+
+        if let Some(color) = name.strip_suffix('s').and_then(Color::builtin) {
+            return Ok(color);
+        }
+
+        Ok(Color::from_str(&name)?)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{cube::Color, round::Round, Game};
+
+/// A numeric measure of a round, that can be compared to build a [`Query`]
+pub struct Measure(Box<dyn Fn(&Round) -> i32>);
+
+impl Measure {
+    /// Create a measure from a closure
+    #[inline(always)]
+    pub fn new(measure: impl Fn(&Round) -> i32 + 'static) -> Self {
+        Self(Box::new(measure))
+    }
+
+    /// The highest amount of cubes of a color drawn at once
+    #[inline(always)]
+    pub fn max(color: Color) -> Self {
+        Self::new(move |round| round.get_minimum_set().get(&color).copied().unwrap_or(0))
+    }
+
+    /// The total amount of cubes of a color drawn over all draws
+    #[inline(always)]
+    pub fn total(color: Color) -> Self {
+        Self::new(move |round| round.draws().iter().filter_map(|draw| draw.cubes().get(&color)).sum())
+    }
+
+    /// The amount of draws
+    #[inline(always)]
+    pub fn draws() -> Self {
+        Self::new(|round| round.draws().len().try_into().unwrap_or(i32::MAX))
+    }
+
+    /// Get the value of the measure for a round
+    #[inline(always)]
+    pub fn measure(&self, round: &Round) -> i32 {
+        (self.0)(round)
+    }
+
+    /// Compare the measure using the predicate
+    #[inline(always)]
+    fn compare(self, predicate: impl Fn(i32) -> bool + 'static) -> Query {
+        Query::new(move |round| predicate(self.measure(round)))
+    }
+
+    /// Match rounds where the measure is greater than the value
+    #[inline(always)]
+    pub fn gt(self, value: i32) -> Query {
+        self.compare(move |measure| measure > value)
+    }
+
+    /// Match rounds where the measure is greater than or equal to the value
+    #[inline(always)]
+    pub fn ge(self, value: i32) -> Query {
+        self.compare(move |measure| measure >= value)
+    }
+
+    /// Match rounds where the measure is less than the value
+    #[inline(always)]
+    pub fn lt(self, value: i32) -> Query {
+        self.compare(move |measure| measure < value)
+    }
+
+    /// Match rounds where the measure is less than or equal to the value
+    #[inline(always)]
+    pub fn le(self, value: i32) -> Query {
+        self.compare(move |measure| measure <= value)
+    }
+
+    /// Match rounds where the measure is equal to the value
+    #[inline(always)]
+    pub fn eq(self, value: i32) -> Query {
+        self.compare(move |measure| measure == value)
+    }
+}
+
+/// A predicate over rounds
+///
+/// e.g. rounds with more than 10 red and fewer than 3 draws:
+/// ```
+/// # use cube_game::{cube::Color, query::Measure};
+/// let query = Measure::max(Color::RED).gt(10).and(Measure::draws().lt(3));
+/// ```
+pub struct Query(Box<dyn Fn(&Round) -> bool>);
+
+impl Query {
+    /// Create a query from a closure
+    #[inline(always)]
+    pub fn new(predicate: impl Fn(&Round) -> bool + 'static) -> Self {
+        Self(Box::new(predicate))
+    }
+
+    /// Check if a round matches the query
+    #[inline(always)]
+    pub fn matches(&self, round: &Round) -> bool {
+        (self.0)(round)
+    }
+
+    /// Match rounds matching both queries
+    #[inline(always)]
+    pub fn and(self, other: Self) -> Self {
+        Self::new(move |round| self.matches(round) && other.matches(round))
+    }
+
+    /// Match rounds matching either query
+    #[inline(always)]
+    pub fn or(self, other: Self) -> Self {
+        Self::new(move |round| self.matches(round) || other.matches(round))
+    }
+}
+
+impl std::ops::Not for Query {
+    type Output = Self;
+
+    /// Match rounds not matching the query
+    #[inline(always)]
+    fn not(self) -> Self::Output {
+        Self::new(move |round| !self.matches(round))
+    }
+}
+
+impl Game {
+    /// Get the IDs of all rounds matching the query
+    pub fn query(&self, query: &Query) -> Vec<i32> {
+        self.rounds
+            .iter()
+            .filter(|round| query.matches(round))
+            .map(|round| *round.id())
+            .collect()
+    }
+}
@@ -1,4 +1,8 @@
-use crate::cube::{Color, ColorError, Cubes};
+use crate::{
+    cube::{Color, ColorError, Cubes},
+    parser::Parser,
+    validation::{ValidationReport, Violation},
+};
 use std::{num::ParseIntError, str::FromStr};
 
 /// Error type for parsing rounds
@@ -8,6 +12,15 @@ pub enum RoundError {
     ParseInt(ParseIntError),
     ParseColor(ColorError),
     DuplicateCubes(Color),
+    NoDraws,
+    NegativeId(i32),
+    NegativeCount(Color, i32),
+    UnprintableColor(Color),
+    InvalidCharacter(char),
+    UnexpectedToken(String),
+    MissingCount(String),
+    MissingColor(i32),
+    TrailingComma,
 }
 
 impl std::error::Error for RoundError {}
@@ -27,8 +40,17 @@ impl From<ColorError> for RoundError {
 impl std::fmt::Display for RoundError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::DuplicateCubes(color) => write!(f, "Got duplicate cubes with {color:?}"),
+            Self::DuplicateCubes(color) => write!(f, "Got duplicate cubes with {color}"),
             Self::NoId => write!(f, "Round id couldn't be determined"),
+            Self::NoDraws => write!(f, "Round has no draws"),
+            Self::NegativeId(id) => write!(f, "Negative round id {id}"),
+            Self::NegativeCount(color, count) => write!(f, "Negative count {count} of {color}"),
+            Self::UnprintableColor(color) => write!(f, "Color '{color}' can't be parsed back from text"),
+            Self::InvalidCharacter(c) => write!(f, "Invalid character '{c}'"),
+            Self::UnexpectedToken(token) => write!(f, "Unexpected token '{token}'"),
+            Self::MissingCount(color) => write!(f, "Missing count before color '{color}'"),
+            Self::MissingColor(count) => write!(f, "Missing color after count {count}"),
+            Self::TrailingComma => write!(f, "Expected cubes after comma"),
             Self::ParseColor(wrong_color) => write!(f, "Failed to parse color '{wrong_color}'"),
             Self::ParseInt(int_err) => write!(f, "Failed to parse integer {int_err:?}"),
         }
@@ -36,67 +58,186 @@ impl std::fmt::Display for RoundError {
 }
 
 /// A draw of cubes
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Draw {
     cubes: Cubes,
+    order: Vec<Color>,
 }
 
-impl FromStr for Draw {
-    type Err = RoundError;
+impl Draw {
+    /// Create a builder for a draw
+    #[inline(always)]
+    pub fn builder() -> DrawBuilder {
+        DrawBuilder::default()
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Create cubes
+    /// Turn the draw back into a builder, for editing
+    #[inline(always)]
+    pub fn into_builder(self) -> DrawBuilder {
+        DrawBuilder {
+            cubes: self.iter().collect(),
+        }
+    }
+
+    /// Check if a color is read back as itself when parsed
+    ///
+    /// The name has to be a single lowercase word, and can't be the plural of a built in color.
+    /// As neither depends on which other colors exist, a printable color stays printable.
+    #[inline(always)]
+    pub fn is_printable(color: &Color) -> bool {
+        let name = color.name();
+
+        !name.is_empty()
+            && name.chars().all(|c| c.is_alphabetic() && !c.is_uppercase())
+            && name.strip_suffix('s').and_then(Color::builtin).is_none()
+    }
+
+    /// Get the cubes of the draw
+    #[inline(always)]
+    pub const fn cubes(&self) -> &Cubes {
+        &self.cubes
+    }
+
+    /// Iterate over the cubes of the draw, in the order they were added
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = (Color, i32)> + '_ {
+        self.order.iter().map(|color| (color.clone(), self.cubes[color]))
+    }
+}
+
+impl std::fmt::Display for Draw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cubes = self
+            .iter()
+            .map(|(color, count)| format!("{count} {color}"))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+/// A builder for a [`Draw`]
+#[derive(Debug, Default, Clone)]
+pub struct DrawBuilder {
+    cubes: Vec<(Color, i32)>,
+}
+
+impl DrawBuilder {
+    /// Add cubes of a color to the draw
+    #[inline(always)]
+    pub fn cube(mut self, color: Color, count: i32) -> Self {
+        self.cubes.push((color, count));
+        self
+    }
+
+    /// Build the draw, failing if a color was added more than once
+    ///
+    /// To keep the text of the draw parseable, negative counts are rejected, as are colors
+    /// that would be read back differently (see [`Draw::is_printable`]).
+    pub fn build(self) -> Result<Draw, RoundError> {
         let mut cubes = Cubes::new();
+        let mut order = Vec::with_capacity(self.cubes.len());
+
+        self.cubes.into_iter().try_for_each(|(color, count)| {
+            if count < 0 {
+                return Err(RoundError::NegativeCount(color, count));
+            }
 
-        s
-            // Remove all commas
-            .replace(',', "")
-            // Split the str
-            .split(' ')
-            // Remove empty strings
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            // Create iterator of chunks with (<count>, <color>)
-            .chunks(2)
-            .try_for_each(|chunk| {
-                let count = chunk[0].parse::<i32>()?;
-                let color = Color::from_str(chunk[1])?;
+            if !Draw::is_printable(&color) {
+                return Err(RoundError::UnprintableColor(color));
+            }
 
-                if cubes.insert(color, count).is_some() {
-                    return Err(RoundError::DuplicateCubes(color));
-                }
+            if cubes.insert(color.clone(), count).is_some() {
+                return Err(RoundError::DuplicateCubes(color));
+            }
 
-                Ok(())
-            })?;
+            order.push(color);
 
-        Ok(Self { cubes })
+            Ok(())
+        })?;
+
+        Ok(Draw { cubes, order })
+    }
+}
+
+impl FromStr for Draw {
+    type Err = RoundError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let draw = parser.draw()?;
+        parser.finish()?;
+
+        Ok(draw)
     }
 }
 
 /// A round of the game
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Round {
     id: i32,
     draws: Vec<Draw>,
 }
 
 impl Round {
+    /// Create a builder for a round with the given ID
+    #[inline(always)]
+    pub fn builder(id: i32) -> RoundBuilder {
+        RoundBuilder { id, draws: Vec::new() }
+    }
+
+    /// Turn the round back into a builder, for editing
+    #[inline(always)]
+    pub fn into_builder(self) -> RoundBuilder {
+        RoundBuilder {
+            id: self.id,
+            draws: self.draws,
+        }
+    }
+
     /// Checks if the round is valid
     #[inline(always)]
     pub fn is_valid(&self, limits: &Cubes) -> bool {
+        self.violations(limits).next().is_none()
+    }
+
+    /// Get every draw exceeding the limits
+    ///
+    /// Colors that are non-existant in the limits have no limit, and are never violations.
+    #[inline(always)]
+    pub fn violations<'a>(&'a self, limits: &'a Cubes) -> impl Iterator<Item = Violation> + 'a {
         // Iterate over all draws
-        self.draws.iter().all(|draw| {
+        self.draws.iter().enumerate().flat_map(move |(index, draw)| {
             // Iterate over the cubes in that draw
-            draw.cubes.iter().all(|(color, count)| {
-                // Grab the limit for the cube's color
-                limits
-                    .get(color)
-                    // Check if the amount of cubes is within the limit
-                    .map(|limit| count <= limit)
-                    // If the color is non-existant in the limits, return true, as it then has no limit.
-                    .unwrap_or(true)
+            draw.cubes.iter().filter_map(move |(color, count)| {
+                // Grab the limit for the cube's color, and check if the amount of cubes exceeds it
+                let limit = limits.get(color).filter(|limit| count > limit)?;
+                Some(Violation::new(self.id, index, color.clone(), *count, *limit))
             })
         })
     }
 
+    /// Get a validation report, listing every violating draw
+    #[inline(always)]
+    pub fn validate(&self, limits: &Cubes) -> ValidationReport {
+        let mut violations = self.violations(limits).collect::<Vec<_>>();
+
+        // Hashmap iteration order is random, so sort for a stable report
+        violations.sort_by(|a, b| (a.draw(), a.color()).cmp(&(b.draw(), b.color())));
+
+        ValidationReport::new(self.id, violations)
+    }
+
+    /// Checks that the round only uses colors listed in the limits
+    #[inline(always)]
+    pub fn check_colors(&self, limits: &Cubes) -> Result<(), RoundError> {
+        self.draws
+            .iter()
+            .flat_map(|draw| draw.cubes.keys())
+            .find(|color| !limits.contains_key(color))
+            .map_or(Ok(()), |color| Err(ColorError(color.name().to_string()).into()))
+    }
+
     /// Get the minimum set of cubes
     #[inline(always)]
     pub fn get_minimum_set(&self) -> Cubes {
@@ -111,7 +252,7 @@ impl Round {
                 let cube_count = *count;
                 // Get the color from set, if its already there
                 minimum_set
-                    .entry(*color)
+                    .entry(color.clone())
                     // Check if the current count is lower than the cubes-count
                     .and_modify(|count| {
                         if *count < cube_count {
@@ -131,32 +272,72 @@ impl Round {
     pub const fn id(&self) -> &i32 {
         &self.id
     }
+
+    /// Get the draws of the round
+    #[inline(always)]
+    pub fn draws(&self) -> &[Draw] {
+        &self.draws
+    }
+}
+
+impl std::fmt::Display for Round {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let draws = self.draws.iter().map(Draw::to_string).collect::<Vec<_>>();
+        write!(f, "Game {}: {}", self.id, draws.join("; "))
+    }
+}
+
+/// A builder for a [`Round`]
+#[derive(Debug, Clone)]
+pub struct RoundBuilder {
+    id: i32,
+    draws: Vec<Draw>,
+}
+
+impl RoundBuilder {
+    /// Set the ID of the round
+    #[inline(always)]
+    pub fn id(mut self, id: i32) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Add a draw to the round
+    #[inline(always)]
+    pub fn draw(mut self, draw: Draw) -> Self {
+        self.draws.push(draw);
+        self
+    }
+
+    /// Get the draws added so far, for editing
+    #[inline(always)]
+    pub fn draws_mut(&mut self) -> &mut Vec<Draw> {
+        &mut self.draws
+    }
+
+    /// Build the round, failing if it has no draws or a negative id
+    ///
+    /// Neither has a textual representation that parses back, so they are rejected.
+    pub fn build(self) -> Result<Round, RoundError> {
+        if self.draws.is_empty() {
+            return Err(RoundError::NoDraws);
+        }
+
+        if self.id < 0 {
+            return Err(RoundError::NegativeId(self.id));
+        }
+
+        Ok(Round {
+            id: self.id,
+            draws: self.draws,
+        })
+    }
 }
 
 impl FromStr for Round {
     type Err = RoundError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Split the string to "Game <id>" | "<draws>"
-        let split = s.split(':').collect::<Vec<_>>();
-
-        // Try to get the id from the last part of the first part of the split
-        let id = if let Some(id_str) = split[0].split(' ').last() {
-            // The id exists where we expect, so parse it to i32
-            id_str.parse::<i32>()?
-        } else {
-            // Return an error,
-            return Err(RoundError::NoId);
-        };
-
-        let draws = split[1]
-            // Split the draw into seperate draws
-            .split(';')
-            // Parse each draw
-            .map(Draw::from_str)
-            // Handle result
-            .collect::<Result<Vec<_>, RoundError>>()?;
-
-        Ok(Self { id, draws })
+        Parser::new(s).round()
     }
 }
@@ -0,0 +1,113 @@
+use crate::{cube::Color, Cubes, Game};
+use std::collections::BTreeMap;
+
+/// Aggregate statistics over every round of a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    totals: Cubes,
+    maximums: Cubes,
+    draws: usize,
+    draws_per_round: BTreeMap<usize, usize>,
+}
+
+impl Statistics {
+    /// Get the total amount of cubes drawn per color
+    #[inline(always)]
+    pub const fn totals(&self) -> &Cubes {
+        &self.totals
+    }
+
+    /// Get the total amount of cubes drawn of a color
+    #[inline(always)]
+    pub fn total(&self, color: &Color) -> i32 {
+        self.totals.get(color).copied().unwrap_or(0)
+    }
+
+    /// Get the mean amount of cubes of a color per draw
+    ///
+    /// Draws without the color count as drawing zero of it.
+    #[inline(always)]
+    pub fn mean(&self, color: &Color) -> f64 {
+        if self.draws == 0 {
+            return 0.0;
+        }
+
+        f64::from(self.total(color)) / self.draws as f64
+    }
+
+    /// Get the highest amount of cubes drawn at once per color, across all rounds
+    #[inline(always)]
+    pub const fn maximums(&self) -> &Cubes {
+        &self.maximums
+    }
+
+    /// Get the highest amount of cubes of a color drawn at once, across all rounds
+    #[inline(always)]
+    pub fn max(&self, color: &Color) -> i32 {
+        self.maximums.get(color).copied().unwrap_or(0)
+    }
+
+    /// Get the total amount of draws
+    #[inline(always)]
+    pub const fn draws(&self) -> &usize {
+        &self.draws
+    }
+
+    /// Get the distribution of draws per round, as `draws -> amount of rounds`
+    #[inline(always)]
+    pub const fn draws_per_round(&self) -> &BTreeMap<usize, usize> {
+        &self.draws_per_round
+    }
+}
+
+impl Game {
+    /// Get aggregate statistics over all rounds
+    pub fn get_statistics(&self) -> Statistics {
+        let mut totals = Cubes::new();
+        let mut draws_per_round = BTreeMap::new();
+
+        self.rounds.iter().for_each(|round| {
+            *draws_per_round.entry(round.draws().len()).or_insert(0) += 1;
+
+            round
+                .draws()
+                .iter()
+                .flat_map(|draw| draw.cubes().iter())
+                .for_each(|(color, count)| *totals.entry(color.clone()).or_insert(0) += count);
+        });
+
+        // The maximums are the minimum set across every round
+        let maximums =
+            self.rounds
+                .iter()
+                .map(|round| round.get_minimum_set())
+                .fold(Cubes::new(), |mut maximums, set| {
+                    set.iter().for_each(|(color, count)| {
+                        let max = maximums.entry(color.clone()).or_insert(*count);
+                        *max = (*max).max(*count);
+                    });
+                    maximums
+                });
+
+        Statistics {
+            totals,
+            maximums,
+            draws: self.rounds.iter().map(|round| round.draws().len()).sum(),
+            draws_per_round,
+        }
+    }
+
+    /// Get the IDs of the feasible rounds for each of the candidate limits
+    pub fn get_feasible_ids(&self, candidates: &[Cubes]) -> Vec<Vec<i32>> {
+        candidates
+            .iter()
+            .map(|limits| {
+                self.rounds
+                    .iter()
+                    .filter(|round| round.is_valid(limits))
+                    .map(|round| *round.id())
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::cube::Color;
+
+/// A draw that exceeded the limit for one of its colors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    round_id: i32,
+    draw: usize,
+    color: Color,
+    count: i32,
+    limit: i32,
+}
+
+impl Violation {
+    /// Create a new violation
+    #[inline(always)]
+    pub const fn new(round_id: i32, draw: usize, color: Color, count: i32, limit: i32) -> Self {
+        Self {
+            round_id,
+            draw,
+            color,
+            count,
+            limit,
+        }
+    }
+
+    /// Get the ID of the round the violation happened in
+    #[inline(always)]
+    pub const fn round_id(&self) -> &i32 {
+        &self.round_id
+    }
+
+    /// Get the (zero-based) index of the violating draw within its round
+    #[inline(always)]
+    pub const fn draw(&self) -> &usize {
+        &self.draw
+    }
+
+    /// Get the color that exceeded its limit
+    #[inline(always)]
+    pub const fn color(&self) -> &Color {
+        &self.color
+    }
+
+    /// Get the observed count of cubes
+    #[inline(always)]
+    pub const fn count(&self) -> &i32 {
+        &self.count
+    }
+
+    /// Get the limit that was exceeded
+    #[inline(always)]
+    pub const fn limit(&self) -> &i32 {
+        &self.limit
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Game {}, draw {}: {} {} exceeds limit of {}",
+            self.round_id, self.draw, self.count, self.color, self.limit
+        )
+    }
+}
+
+/// A validation report for a single round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    round_id: i32,
+    violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Create a new validation report
+    #[inline(always)]
+    pub const fn new(round_id: i32, violations: Vec<Violation>) -> Self {
+        Self { round_id, violations }
+    }
+
+    /// Get the ID of the validated round
+    #[inline(always)]
+    pub const fn round_id(&self) -> &i32 {
+        &self.round_id
+    }
+
+    /// Get all violations found in the round
+    #[inline(always)]
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Checks if the round had no violations
+    #[inline(always)]
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    part::{Part, PartIndex},
+    rule::GearRule,
+    symbol::Symbol,
+    EngineSchematic, SchematicCoordinate, SchematicError,
+};
+use std::ops::RangeInclusive;
+
+impl EngineSchematic {
+    /// Get the sum of all parts, maintained incrementally while editing
+    #[inline(always)]
+    pub const fn part_sum(&self) -> &i32 {
+        &self.part_sum
+    }
+
+    /// Get the gear ratio, maintained incrementally while editing
+    #[inline(always)]
+    pub const fn gear_ratio(&self) -> &i32 {
+        &self.gear_ratio
+    }
+
+    /// Set a cell of the schematic to any character
+    ///
+    /// Only the edited line is rescanned, splitting or merging numbers as needed. Parts that
+    /// didn't change keep their ids. The part sum and gear ratio are updated using only the
+    /// lines around the edit. Line breaks and other control characters are rejected, as they
+    /// would change the shape of the schematic.
+    pub fn set_cell(&mut self, coord: SchematicCoordinate, c: char) -> Result<(), SchematicError> {
+        let line = *coord.line();
+
+        if c.is_control() {
+            return Err(SchematicError::InvalidCell(c));
+        }
+
+        if self.cell(coord).ok_or(SchematicError::OutOfBounds(coord))? == c {
+            return Ok(());
+        }
+
+        let (index, column) = (usize::try_from(line)?, usize::try_from(*coord.column())?);
+
+        // Scan the edited line before changing anything, so a failed edit leaves the schematic untouched
+        let mut cells = self.cells[index].clone();
+        cells[column] = c;
+
+        let contents = cells.iter().collect::<String>();
+        let mut next_id = self.next_id;
+        let (_, rescanned) = Self::find_possible_parts((&line, &contents), &mut next_id)?;
+
+        // Only lines next to the edited line can be affected
+        let affected = (line - 1)..=(line + 1);
+        let (part_sum, gear_ratio) = self.totals_of(affected.clone());
+
+        self.cells[index] = cells;
+        self.next_id = next_id;
+        self.replace_line(line, &contents, rescanned);
+
+        let (new_part_sum, new_gear_ratio) = self.totals_of(affected);
+
+        self.part_sum += new_part_sum - part_sum;
+        self.gear_ratio += new_gear_ratio - gear_ratio;
+
+        Ok(())
+    }
+
+    /// Insert a symbol into a cell
+    #[inline(always)]
+    pub fn insert_symbol(&mut self, coord: SchematicCoordinate, symbol: char) -> Result<(), SchematicError> {
+        if !self.symbol_set.is_symbol(symbol) {
+            return Err(SchematicError::InvalidCell(symbol));
+        }
+
+        self.set_cell(coord, symbol)
+    }
+
+    /// Remove a symbol from a cell, replacing it with the filler
+    #[inline(always)]
+    pub fn remove_symbol(&mut self, coord: SchematicCoordinate) -> Result<(), SchematicError> {
+        let c = self.cell(coord).ok_or(SchematicError::OutOfBounds(coord))?;
+
+        if !self.symbol_set.is_symbol(c) {
+            return Err(SchematicError::InvalidCell(c));
+        }
+
+        self.set_cell(coord, *self.symbol_set.filler())
+    }
+
+    /// Change a cell to a digit
+    #[inline(always)]
+    pub fn set_digit(&mut self, coord: SchematicCoordinate, digit: u32) -> Result<(), SchematicError> {
+        let c = char::from_digit(digit, 10).ok_or(SchematicError::InvalidDigit(digit))?;
+        self.set_cell(coord, c)
+    }
+
+    /// Get the character of a cell
+    #[inline(always)]
+    pub fn cell(&self, coord: SchematicCoordinate) -> Option<char> {
+        let line = usize::try_from(*coord.line()).ok()?;
+        let column = usize::try_from(*coord.column()).ok()?;
+        self.cells.get(line)?.get(column).copied()
+    }
+
+    /// Get the symbols on a line
+    #[inline(always)]
+    fn symbols_on(&self, line: i32) -> &[Symbol] {
+        let from = self.symbols.partition_point(|s| *s.coord().line() < line);
+        let to = self.symbols.partition_point(|s| *s.coord().line() <= line);
+        &self.symbols[from..to]
+    }
+
+    /// Get the part sum and gear ratio contributed by the parts and gears on the lines
+    fn totals_of(&self, lines: RangeInclusive<i32>) -> (i32, i32) {
+        let gear_rule = GearRule::gear_ratio();
+
+        lines.fold((0, 0), |(part_sum, gear_ratio), line| {
+            let parts = self
+                .possible_parts
+                .get(&line)
+                .map(|parts| {
+                    parts
+                        .iter()
+                        .filter(|part| self.is_part(part))
+                        .map(|part| part.number())
+                        .sum::<i32>()
+                })
+                .unwrap_or(0);
+
+            let gears = self
+                .symbols_on(line)
+                .iter()
+                .filter(|symbol| symbol.character() == gear_rule.symbol())
+                .filter_map(|symbol| {
+                    let numbers = self
+                        .get_parts_around_symbol(symbol)
+                        .iter()
+                        .map(|part| *part.number())
+                        .collect::<Vec<_>>();
+                    gear_rule.apply(&numbers)
+                })
+                .sum::<i32>();
+
+            (part_sum + parts, gear_ratio + gears)
+        })
+    }
+
+    /// Check if a possible part is adjacent to any symbol
+    #[inline(always)]
+    fn is_part(&self, part: &Part) -> bool {
+        let line = *part.start().line();
+
+        ((line - 1)..=(line + 1)).any(|l| {
+            self.symbols_on(l)
+                .iter()
+                .any(|symbol| part.adjacent_to(symbol.coord().column()))
+        })
+    }
+
+    /// Replace the symbols and parts of a line with those of its edited contents
+    fn replace_line(&mut self, line: i32, contents: &String, rescanned: PartIndex) {
+        // Replace the symbols of the line, keeping the symbols sorted in reading order
+        let from = self.symbols.partition_point(|s| *s.coord().line() < line);
+        let to = self.symbols.partition_point(|s| *s.coord().line() <= line);
+        self.symbols
+            .splice(from..to, Self::find_symbols((&line, contents), &self.symbol_set));
+
+        // Give back the old ids of unchanged parts
+        let old = self.possible_parts.remove(&line).unwrap_or_default();
+
+        let parts = rescanned
+            .iter()
+            .map(|part| {
+                old.iter()
+                    .find(|old| old.start() == part.start() && old.end() == part.end() && old.number() == part.number())
+                    .map_or(*part, |old| *old)
+            })
+            .collect();
+
+        self.possible_parts.insert(line, PartIndex::new(parts));
+    }
+}
+
+impl std::fmt::Display for EngineSchematic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .cells
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    part::{Part, PartId, Parts},
+    symbol::{Symbol, SymbolId},
+    EngineSchematic,
+};
+use std::collections::BTreeMap;
+
+/// A bipartite graph between the numbers and symbols of a schematic
+///
+/// A number is a part if it is connected to at least one symbol.
+#[derive(Debug)]
+pub struct PartGraph {
+    numbers: BTreeMap<PartId, Part>,
+    symbols: Vec<Symbol>,
+    part_symbols: BTreeMap<PartId, Vec<SymbolId>>,
+    symbol_parts: Vec<Vec<PartId>>,
+}
+
+impl PartGraph {
+    /// Build the graph of a schematic
+    pub fn new(schematic: &EngineSchematic) -> Self {
+        let numbers = schematic
+            .possible_parts
+            .values()
+            .flat_map(|parts| parts.iter())
+            .map(|part| (*part.id(), *part))
+            .collect::<BTreeMap<_, _>>();
+
+        let symbols = schematic.symbols().to_vec();
+
+        let symbol_parts = symbols
+            .iter()
+            .map(|symbol| {
+                schematic
+                    .get_parts_around_symbol(symbol)
+                    .iter()
+                    .map(|part| *part.id())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut part_symbols = BTreeMap::<PartId, Vec<SymbolId>>::new();
+
+        symbol_parts.iter().enumerate().for_each(|(index, parts)| {
+            parts
+                .iter()
+                .for_each(|part| part_symbols.entry(*part).or_default().push(SymbolId::new(index)))
+        });
+
+        Self {
+            numbers,
+            symbols,
+            part_symbols,
+            symbol_parts,
+        }
+    }
+
+    /// Get the unique parts, in reading order
+    #[inline(always)]
+    pub fn parts(&self) -> Parts {
+        self.part_symbols.keys().map(|id| &self.numbers[id]).collect()
+    }
+
+    /// Get a part (or non-part number) by its id
+    #[inline(always)]
+    pub fn part(&self, id: &PartId) -> Option<&Part> {
+        self.numbers.get(id)
+    }
+
+    /// Get a symbol by its id
+    #[inline(always)]
+    pub fn symbol(&self, id: &SymbolId) -> Option<&Symbol> {
+        self.symbols.get(id.index())
+    }
+
+    /// Get the symbols a part touches
+    #[inline(always)]
+    pub fn symbols_of(&self, part: &PartId) -> &[SymbolId] {
+        self.part_symbols.get(part).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the parts a symbol touches
+    #[inline(always)]
+    pub fn parts_of(&self, symbol: &SymbolId) -> &[PartId] {
+        self.symbol_parts.get(symbol.index()).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the numbers that don't touch any symbols, and therefore aren't parts
+    #[inline(always)]
+    pub fn isolated(&self) -> Parts {
+        self.numbers
+            .values()
+            .filter(|part| !self.part_symbols.contains_key(part.id()))
+            .collect()
+    }
+
+    /// Get the connected components of parts, linked through shared symbols
+    ///
+    /// Each component is sorted by id, and the components are sorted by their first id.
+    pub fn components(&self) -> Vec<Vec<PartId>> {
+        let ids = self.part_symbols.keys().copied().collect::<Vec<_>>();
+        let index_of = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect::<BTreeMap<_, _>>();
+
+        // Union-find over the part indices
+        let mut parents = (0..ids.len()).collect::<Vec<_>>();
+
+        fn find(parents: &mut [usize], i: usize) -> usize {
+            let mut root = i;
+            while parents[root] != root {
+                root = parents[root];
+            }
+
+            // Compress the path
+            let mut i = i;
+            while parents[i] != root {
+                let next = parents[i];
+                parents[i] = root;
+                i = next;
+            }
+
+            root
+        }
+
+        self.symbol_parts.iter().for_each(|parts| {
+            parts.windows(2).for_each(|pair| {
+                let a = find(&mut parents, index_of[&pair[0]]);
+                let b = find(&mut parents, index_of[&pair[1]]);
+                parents[a.max(b)] = a.min(b);
+            })
+        });
+
+        let mut components = BTreeMap::<usize, Vec<PartId>>::new();
+
+        (0..ids.len()).for_each(|i| {
+            let root = find(&mut parents, i);
+            components.entry(root).or_default().push(ids[i]);
+        });
+
+        components.into_values().collect()
+    }
+}
@@ -9,14 +9,35 @@ pub mod part;
 /// Symbol module
 pub mod symbol;
 
-use part::{Part, Parts};
-use symbol::Symbol;
+/// Gear rule module
+pub mod rule;
+
+/// Part graph module
+pub mod graph;
+
+/// Rendering module
+pub mod render;
+
+/// Streaming module
+pub mod stream;
+
+/// Editing module
+pub mod edit;
+
+use graph::PartGraph;
+use part::{Part, PartId, PartIndex, Parts};
+use rule::GearRule;
+use symbol::{Symbol, SymbolSet};
 
 /// Error for schematic parsing
 #[derive(Debug)]
 pub enum SchematicError {
     ParseUsize(TryFromIntError),
     ParseInt(ParseIntError),
+    Io(std::io::Error),
+    OutOfBounds(SchematicCoordinate),
+    InvalidCell(char),
+    InvalidDigit(u32),
     Empty,
 }
 
@@ -34,18 +55,28 @@ impl From<ParseIntError> for SchematicError {
     }
 }
 
+impl From<std::io::Error> for SchematicError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
 impl std::fmt::Display for SchematicError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Empty => write!(f, "Schematic is empty"),
             Self::ParseUsize(int_err) => write!(f, "Failed to parse usize: {int_err:?}"),
             Self::ParseInt(int_err) => write!(f, "Failed to parse integer: {int_err:?}"),
+            Self::Io(io_err) => write!(f, "Failed to read schematic: {io_err}"),
+            Self::OutOfBounds(coord) => write!(f, "Coordinate {coord:?} is outside the schematic"),
+            Self::InvalidCell(c) => write!(f, "Invalid cell '{c}'"),
+            Self::InvalidDigit(digit) => write!(f, "Invalid digit {digit}"),
         }
     }
 }
 
 /// A coordinate in a schematic
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SchematicCoordinate {
     line: i32,
     column: i32,
@@ -74,13 +105,24 @@ impl SchematicCoordinate {
 /// A schematic for an engine
 #[derive(Debug)]
 pub struct EngineSchematic {
+    cells: Vec<Vec<char>>,
+    symbol_set: SymbolSet,
     symbols: Vec<Symbol>,
-    possible_parts: BTreeMap<i32, Parts>,
+    possible_parts: BTreeMap<i32, PartIndex>,
+    next_id: usize,
+    part_sum: i32,
+    gear_ratio: i32,
 }
 
 impl EngineSchematic {
     /// Create a new schematic
+    #[inline(always)]
     pub fn new(schematic: &str) -> Result<Self, SchematicError> {
+        Self::with_symbol_set(schematic, &SymbolSet::default())
+    }
+
+    /// Create a new schematic, using the symbol set to decide which characters are symbols
+    pub fn with_symbol_set(schematic: &str, symbol_set: &SymbolSet) -> Result<Self, SchematicError> {
         let schematic: BTreeMap<i32, String> = (0i32..)
             // Enumerate all lines
             .zip(schematic.lines().map(str::to_string))
@@ -90,44 +132,95 @@ impl EngineSchematic {
         let symbols = schematic
             .iter()
             // Use map to find all symbols
-            .flat_map(Self::find_symbols)
+            .flat_map(|line| Self::find_symbols(line, symbol_set))
             .collect();
 
+        let mut next_id = 0;
+
         let possible_parts = schematic
             .iter()
-            // Use map to find all parts
-            .map(Self::find_possible_parts)
-            .collect::<Result<BTreeMap<i32, Parts>, SchematicError>>()?;
+            // Use map to find all parts, numbering them in reading order
+            .map(|line| Self::find_possible_parts(line, &mut next_id))
+            .collect::<Result<BTreeMap<i32, PartIndex>, SchematicError>>()?;
+
+        let cells = schematic.into_values().map(|line| line.chars().collect()).collect();
 
-        Ok(Self {
+        let mut schematic = Self {
+            cells,
+            symbol_set: symbol_set.clone(),
             symbols,
             possible_parts,
-        })
+            next_id,
+            part_sum: 0,
+            gear_ratio: 0,
+        };
+
+        schematic.part_sum = schematic.get_parts().sum();
+        schematic.gear_ratio = schematic.get_gear_ratio();
+
+        Ok(schematic)
     }
 
     /// Get all parts in the schematic
+    ///
+    /// A part touching multiple symbols is only included once.
     #[inline(always)]
     pub fn get_parts(&self) -> Parts {
-        self.symbols
+        self.graph().parts()
+    }
+
+    /// Get the graph between the parts and symbols of the schematic
+    #[inline(always)]
+    pub fn graph(&self) -> PartGraph {
+        PartGraph::new(self)
+    }
+
+    /// Get all parts touching a symbol of one of the given characters
+    #[inline(always)]
+    pub fn get_parts_touching(&self, characters: &[char]) -> Parts {
+        let parts = self
+            .symbols
             .iter()
-            .flat_map(|coord| self.get_parts_around_symbol(coord))
-            .collect()
+            .filter(|s| characters.contains(s.character()))
+            .flat_map(|s| self.get_parts_around_symbol(s))
+            .map(|p| (*p.id(), p))
+            .collect::<BTreeMap<_, _>>();
+
+        parts.into_values().collect()
+    }
+
+    /// Get the part covering a cell, if any
+    #[inline(always)]
+    pub fn parts_at(&self, coord: SchematicCoordinate) -> Option<&Part> {
+        self.possible_parts.get(coord.line())?.at(coord.column())
+    }
+
+    /// Get all symbols in the schematic
+    #[inline(always)]
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
     }
 
     /// Get the gear ratio of the schematic
     #[inline(always)]
     pub fn get_gear_ratio(&self) -> i32 {
+        self.apply_rule(&GearRule::gear_ratio())
+    }
+
+    /// Get the sum of the values of all symbols matching the gear rule
+    #[inline(always)]
+    pub fn apply_rule(&self, rule: &GearRule) -> i32 {
         self.symbols
             .iter()
-            .filter(|s| s.is_gear())
+            .filter(|s| s.character() == rule.symbol())
             .filter_map(|s| {
-                let parts = self.get_parts_around_symbol(s);
-
-                if parts.len() == 2 {
-                    return Some(parts.iter().map(|p| p.number()).product::<i32>());
-                }
+                let numbers = self
+                    .get_parts_around_symbol(s)
+                    .iter()
+                    .map(|p| *p.number())
+                    .collect::<Vec<_>>();
 
-                None
+                rule.apply(&numbers)
             })
             .sum()
     }
@@ -140,12 +233,7 @@ impl EngineSchematic {
         // Loop from the line above to the line below
         for l in (symbol.coord().line().saturating_sub(1))..=(symbol.coord().line() + 1) {
             if let Some(parts) = self.possible_parts.get(&l) {
-                let mut vec = parts
-                    .iter()
-                    .filter(|p| p.adjacent_to(symbol.coord().column()))
-                    .copied()
-                    .collect();
-                adjacent_parts.append(&mut vec);
+                adjacent_parts.extend_from_slice(parts.adjacent_to(symbol.coord().column()));
             }
         }
 
@@ -154,7 +242,7 @@ impl EngineSchematic {
 
     /// Find symbols in a line
     #[inline(always)]
-    fn find_symbols(line: (&i32, &String)) -> Vec<Symbol> {
+    fn find_symbols(line: (&i32, &String), symbol_set: &SymbolSet) -> Vec<Symbol> {
         // Extract tuple values
         let (line, contents) = line;
 
@@ -162,18 +250,15 @@ impl EngineSchematic {
             .chars()
             // Enumerate to get column
             .zip(0i32..)
-            // Map and match
-            .filter_map(|(c, column)| match c {
-                '.' => None,
-                _ if c.is_ascii_punctuation() => Some(Symbol::new(SchematicCoordinate::new(*line, column), '*' == c)),
-                _ => None,
-            })
+            // Keep the symbols only
+            .filter(|(c, _)| symbol_set.is_symbol(*c))
+            .map(|(c, column)| Symbol::new(SchematicCoordinate::new(*line, column), c))
             .collect::<Vec<_>>()
     }
 
     /// Find possible parts in a line
     #[inline(always)]
-    fn find_possible_parts(line: (&i32, &String)) -> Result<(i32, Parts), SchematicError> {
+    fn find_possible_parts(line: (&i32, &String), next_id: &mut usize) -> Result<(i32, PartIndex), SchematicError> {
         // Extract tuple values
         let (line, contents) = line;
 
@@ -205,11 +290,14 @@ impl EngineSchematic {
                 let start = SchematicCoordinate::new(*line, columns[0]);
                 let end = SchematicCoordinate::new(*line, *columns.last().unwrap_or(&columns[0]));
 
-                Ok(Part::new(number, start, end))
+                let id = PartId::new(*next_id);
+                *next_id += 1;
+
+                Ok(Part::new(id, number, start, end))
             })
             .collect::<Result<Parts, SchematicError>>()?;
 
-        Ok((*line, parts))
+        Ok((*line, PartIndex::new(parts)))
     }
 }
 
@@ -233,4 +321,222 @@ mod tests {
         let schem = EngineSchematic::new(EXAMPLE).expect("Failed to create schematic");
         assert_eq!(schem.get_parts().sum(), 4361)
     }
+
+    #[test]
+    fn symbol_sets() {
+        let schem = EngineSchematic::new(EXAMPLE).expect("Failed to create schematic");
+        assert_eq!(schem.get_parts_touching(&['#', '$']).sum(), 633 + 664);
+        assert_eq!(schem.get_parts_touching(&['+']).sum(), 592);
+
+        let symbol_set = SymbolSet::default().with_symbols(&['*']);
+        let schem = EngineSchematic::with_symbol_set(EXAMPLE, &symbol_set).expect("Failed to create schematic");
+        assert_eq!(schem.get_parts().sum(), 467 + 35 + 617 + 755 + 598);
+
+        let schem = EngineSchematic::with_symbol_set(&EXAMPLE.replace('.', " "), &SymbolSet::new(' '))
+            .expect("Failed to create schematic");
+        assert_eq!(schem.get_parts().sum(), 4361);
+        assert_eq!(schem.symbols().iter().filter(|s| *s.character() == '*').count(), 3);
+    }
+
+    #[test]
+    fn gear_rules() {
+        use rule::{Adjacency, Reducer};
+
+        let schem = EngineSchematic::new(EXAMPLE).expect("Failed to create schematic");
+        assert_eq!(schem.get_gear_ratio(), 467835);
+
+        let rule = GearRule::new('*', Adjacency::AtLeast(1), Reducer::Sum);
+        assert_eq!(schem.apply_rule(&rule), 467 + 35 + 617 + 755 + 598);
+
+        let rule = GearRule::new('*', Adjacency::Range(1..=1), Reducer::Max);
+        assert_eq!(schem.apply_rule(&rule), 617);
+
+        let rule = GearRule::new('#', Adjacency::Exact(1), Reducer::custom(|n| n.iter().sum::<i32>() * 2));
+        assert_eq!(schem.apply_rule(&rule), 633 * 2);
+    }
+
+    #[test]
+    fn part_graph() {
+        use symbol::SymbolId;
+
+        // 12 touches both symbols, and 5 is isolated
+        let schem = EngineSchematic::new("12*3\n#...\n...5\n7+8.").expect("Failed to create schematic");
+        let graph = schem.graph();
+
+        assert_eq!(schem.get_parts().sum(), 12 + 3 + 7 + 8);
+        assert_eq!(
+            graph.isolated().iter().map(|p| *p.number()).collect::<Vec<_>>(),
+            vec![5]
+        );
+
+        let twelve = *graph.parts()[0].id();
+        assert_eq!(graph.symbols_of(&twelve), &[SymbolId::new(0), SymbolId::new(1)]);
+        assert_eq!(graph.parts_of(&SymbolId::new(0)).len(), 2);
+        assert_eq!(graph.symbol(&SymbolId::new(2)).map(|s| *s.character()), Some('+'));
+
+        let components = graph
+            .components()
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .map(|id| *graph.part(id).expect("Missing part").number())
+                    .collect()
+            })
+            .collect::<Vec<Vec<i32>>>();
+        assert_eq!(components, vec![vec![12, 3], vec![7, 8]]);
+    }
+
+    #[test]
+    fn render() {
+        use render::RenderStyle;
+
+        let schem = EngineSchematic::new("467..114..\n...*......\n..35..#...").expect("Failed to create schematic");
+
+        assert_eq!(
+            schem.render(RenderStyle::Plain),
+            "[467]..(114)..\n...{*}......\n..[35]..<#>...\n\n\
+            Line 0: parts 467, non-parts 114, gear ratios 0\n\
+            Line 1: parts 0, non-parts 0, gear ratios 16345\n\
+            Line 2: parts 35, non-parts 0, gear ratios 0\n"
+        );
+        assert!(schem
+            .render(RenderStyle::Ansi)
+            .starts_with("\x1b[32m467\x1b[0m..\x1b[31m114\x1b[0m"));
+    }
+
+    #[test]
+    fn streaming() {
+        use stream::SchematicStream;
+
+        let totals = SchematicStream::new(EXAMPLE.as_bytes())
+            .totals()
+            .expect("Failed to stream schematic");
+        assert_eq!(totals, (4361, 467835));
+
+        let lines = SchematicStream::new(EXAMPLE.as_bytes())
+            .map(|totals| totals.map(|t| (*t.line(), *t.part_sum(), *t.gear_ratio())))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to stream schematic");
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], (0, 467, 0));
+        assert_eq!(lines[1], (1, 0, 16345));
+        assert_eq!(lines[9], (9, 664 + 598, 0));
+    }
+
+    #[test]
+    fn part_lookup() {
+        let schem = EngineSchematic::new(EXAMPLE).expect("Failed to create schematic");
+
+        let number_at = |line, column| {
+            schem
+                .parts_at(SchematicCoordinate::new(line, column))
+                .map(|p| *p.number())
+        };
+        assert_eq!(number_at(0, 0), Some(467));
+        assert_eq!(number_at(0, 2), Some(467));
+        assert_eq!(number_at(0, 3), None);
+        assert_eq!(number_at(0, 7), Some(114));
+        assert_eq!(number_at(9, 3), Some(664));
+        assert_eq!(number_at(10, 0), None);
+
+        let index = schem.possible_parts.get(&2).expect("Missing line");
+        assert_eq!(
+            index.adjacent_to(&4).iter().map(|p| *p.number()).collect::<Vec<_>>(),
+            vec![35]
+        );
+        assert_eq!(
+            index.adjacent_to(&5).iter().map(|p| *p.number()).collect::<Vec<_>>(),
+            vec![633]
+        );
+        assert!(index.adjacent_to(&10).is_empty());
+    }
+
+    #[test]
+    fn editing() {
+        let mut schem = EngineSchematic::new(EXAMPLE).expect("Failed to create schematic");
+        assert_eq!((*schem.part_sum(), *schem.gear_ratio()), (4361, 467835));
+
+        let id_of_467 = *schem
+            .parts_at(SchematicCoordinate::new(0, 0))
+            .expect("Missing part")
+            .id();
+
+        // Make 114 a part
+        schem
+            .insert_symbol(SchematicCoordinate::new(1, 8), '#')
+            .expect("Failed to insert symbol");
+        // Merge 35 and 633 into a single number
+        schem
+            .set_digit(SchematicCoordinate::new(2, 4), 1)
+            .expect("Failed to set digit");
+        schem
+            .set_digit(SchematicCoordinate::new(2, 5), 2)
+            .expect("Failed to set digit");
+        // Split 592 into 5 and 2
+        schem
+            .set_cell(SchematicCoordinate::new(6, 3), '.')
+            .expect("Failed to set cell");
+        // Remove a gear
+        schem
+            .remove_symbol(SchematicCoordinate::new(8, 5))
+            .expect("Failed to remove symbol");
+
+        let fresh = EngineSchematic::new(&schem.to_string()).expect("Failed to create schematic");
+        assert_eq!(*schem.part_sum(), fresh.get_parts().sum());
+        assert_eq!(*schem.gear_ratio(), fresh.get_gear_ratio());
+        assert_eq!(schem.get_parts().sum(), fresh.get_parts().sum());
+
+        assert_eq!(
+            schem.parts_at(SchematicCoordinate::new(0, 0)).map(|p| *p.id()),
+            Some(id_of_467)
+        );
+        assert_eq!(
+            schem.parts_at(SchematicCoordinate::new(2, 3)).map(|p| *p.number()),
+            Some(3512633)
+        );
+
+        assert!(schem.set_cell(SchematicCoordinate::new(10, 0), '*').is_err());
+        assert!(schem.insert_symbol(SchematicCoordinate::new(0, 3), 'a').is_err());
+        assert!(schem.remove_symbol(SchematicCoordinate::new(0, 3)).is_err());
+        assert!(matches!(
+            schem.set_digit(SchematicCoordinate::new(0, 3), 10),
+            Err(SchematicError::InvalidDigit(10))
+        ));
+
+        // Line breaks would change the shape of the schematic
+        let before = schem.to_string();
+        for c in ['\n', '\r', '\t'] {
+            assert!(matches!(
+                schem.set_cell(SchematicCoordinate::new(0, 3), c),
+                Err(SchematicError::InvalidCell(_))
+            ));
+        }
+        assert_eq!(schem.to_string(), before);
+    }
+
+    #[test]
+    fn failed_edit_is_rolled_back() {
+        let mut schem = EngineSchematic::new("999999999.\n*.........").expect("Failed to create schematic");
+        let before = schem.to_string();
+
+        // Merging the digits overflows the part number, so the edit fails as a whole
+        assert!(matches!(
+            schem.set_digit(SchematicCoordinate::new(0, 9), 9),
+            Err(SchematicError::ParseInt(_))
+        ));
+
+        assert_eq!(schem.to_string(), before);
+        assert_eq!(schem.get_parts().sum(), 999999999);
+        assert_eq!(*schem.part_sum(), 999999999);
+
+        // Later edits still see a consistent schematic
+        schem
+            .insert_symbol(SchematicCoordinate::new(0, 9), '*')
+            .expect("Failed to insert symbol");
+        schem
+            .set_cell(SchematicCoordinate::new(1, 0), '.')
+            .expect("Failed to set cell");
+        assert_eq!(*schem.part_sum(), 999999999);
+        assert_eq!(schem.to_string(), "999999999*\n..........");
+    }
 }
@@ -41,9 +41,68 @@ impl DerefMut for Parts {
     }
 }
 
+/// The parts of a single line, sorted by column
+///
+/// Parts on a line never overlap, so both their start and end columns are sorted,
+/// which allows looking up parts by column with a binary search.
+#[derive(Debug, Default)]
+pub struct PartIndex(Parts);
+
+impl PartIndex {
+    /// Create a new index, sorting the parts by column
+    #[inline(always)]
+    pub fn new(mut parts: Parts) -> Self {
+        parts.sort_unstable_by_key(|part| part.start.column);
+        Self(parts)
+    }
+
+    /// Get the parts adjacent to a column
+    #[inline(always)]
+    pub fn adjacent_to(&self, column: &i32) -> &[Part] {
+        let from = self.0.partition_point(|part| part.end.column < column - 1);
+        let to = self.0.partition_point(|part| part.start.column <= column + 1);
+        &self.0[from..to]
+    }
+
+    /// Get the part covering a column
+    #[inline(always)]
+    pub fn at(&self, column: &i32) -> Option<&Part> {
+        let index = self.0.partition_point(|part| part.end.column < *column);
+        self.0.get(index).filter(|part| part.start.column <= *column)
+    }
+}
+
+impl Deref for PartIndex {
+    type Target = Parts;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// A stable identifier of a part within its schematic
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartId(usize);
+
+impl PartId {
+    /// Create a new part id
+    #[inline(always)]
+    pub const fn new(id: usize) -> Self {
+        Self(id)
+    }
+
+    /// Get the id as an index
+    #[inline(always)]
+    pub const fn index(&self) -> usize {
+        self.0
+    }
+}
+
 /// A part in a schematic
 #[derive(Clone, Copy, Debug)]
 pub struct Part {
+    id: PartId,
     start: SchematicCoordinate,
     end: SchematicCoordinate,
     number: i32,
@@ -52,8 +111,26 @@ pub struct Part {
 impl Part {
     /// Create a new part
     #[inline(always)]
-    pub const fn new(number: i32, start: SchematicCoordinate, end: SchematicCoordinate) -> Self {
-        Self { start, end, number }
+    pub const fn new(id: PartId, number: i32, start: SchematicCoordinate, end: SchematicCoordinate) -> Self {
+        Self { id, start, end, number }
+    }
+
+    /// Get the id of the part
+    #[inline(always)]
+    pub const fn id(&self) -> &PartId {
+        &self.id
+    }
+
+    /// Get the start coordinate of the part
+    #[inline(always)]
+    pub const fn start(&self) -> &SchematicCoordinate {
+        &self.start
+    }
+
+    /// Get the end coordinate of the part
+    #[inline(always)]
+    pub const fn end(&self) -> &SchematicCoordinate {
+        &self.end
     }
 
     /// Check if the part is adjacent to a column
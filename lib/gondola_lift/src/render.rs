@@ -0,0 +1,153 @@
+use crate::{part::PartId, rule::GearRule, symbol::Symbol, EngineSchematic};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// ANSI escape code for resetting the style
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// How to mark up a rendered schematic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// ANSI colors, for terminals
+    Ansi,
+    /// Bracket markup, for plain text
+    Plain,
+}
+
+/// The kinds of annotated cells in a schematic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    Part,
+    NonPart,
+    Gear,
+    Symbol,
+}
+
+impl Highlight {
+    /// Wrap the contents in the markup of the style
+    #[inline(always)]
+    fn wrap(&self, style: RenderStyle, contents: &str) -> String {
+        match style {
+            RenderStyle::Ansi => {
+                let color = match self {
+                    Self::Part => "\x1b[32m",
+                    Self::NonPart => "\x1b[31m",
+                    Self::Gear => "\x1b[1;33m",
+                    Self::Symbol => "\x1b[36m",
+                };
+                format!("{color}{contents}{ANSI_RESET}")
+            }
+            RenderStyle::Plain => {
+                let (open, close) = match self {
+                    Self::Part => ('[', ']'),
+                    Self::NonPart => ('(', ')'),
+                    Self::Gear => ('{', '}'),
+                    Self::Symbol => ('<', '>'),
+                };
+                format!("{open}{contents}{close}")
+            }
+        }
+    }
+}
+
+impl EngineSchematic {
+    /// Render the schematic with highlights, followed by a legend of the sums for each line
+    ///
+    /// Parts, numbers that aren't parts, gears with exactly two parts and other symbols are
+    /// highlighted differently. With [`RenderStyle::Plain`] they are marked as `[467]`, `(114)`,
+    /// `{*}` and `<#>` respectively.
+    pub fn render(&self, style: RenderStyle) -> String {
+        let graph = self.graph();
+        let gear_rule = GearRule::gear_ratio();
+
+        let parts = graph
+            .parts()
+            .iter()
+            .map(|part| *part.id())
+            .collect::<BTreeSet<PartId>>();
+
+        // Group symbols by line, with the gear ratio of any gears
+        let mut symbols = BTreeMap::<i32, Vec<(&Symbol, Option<i32>)>>::new();
+        self.symbols.iter().for_each(|symbol| {
+            let ratio = (symbol.character() == gear_rule.symbol())
+                .then(|| {
+                    let numbers = self
+                        .get_parts_around_symbol(symbol)
+                        .iter()
+                        .map(|part| *part.number())
+                        .collect::<Vec<_>>();
+                    gear_rule.apply(&numbers)
+                })
+                .flatten();
+
+            symbols.entry(*symbol.coord().line()).or_default().push((symbol, ratio));
+        });
+
+        let mut rendered = String::new();
+        let mut legend = Vec::with_capacity(self.cells.len());
+
+        (0i32..).zip(self.cells.iter()).for_each(|(line, cells)| {
+            let line_parts = self
+                .possible_parts
+                .get(&line)
+                .map(|parts| parts.as_slice())
+                .unwrap_or(&[]);
+            let line_symbols = symbols.get(&line).map(Vec::as_slice).unwrap_or(&[]);
+
+            let mut column = 0;
+            while let Some(c) = cells.get(column) {
+                let column_i32 = i32::try_from(column).unwrap_or(i32::MAX);
+
+                if let Some(part) = line_parts.iter().find(|part| *part.start().column() == column_i32) {
+                    let highlight = if parts.contains(part.id()) {
+                        Highlight::Part
+                    } else {
+                        Highlight::NonPart
+                    };
+                    let width = usize::try_from(part.end().column() - part.start().column()).unwrap_or(0) + 1;
+                    let contents = cells[column..(column + width).min(cells.len())]
+                        .iter()
+                        .collect::<String>();
+
+                    rendered.push_str(&highlight.wrap(style, &contents));
+                    column += width;
+                    continue;
+                }
+
+                match line_symbols
+                    .iter()
+                    .find(|(symbol, _)| *symbol.coord().column() == column_i32)
+                {
+                    Some((_, Some(_))) => rendered.push_str(&Highlight::Gear.wrap(style, &c.to_string())),
+                    Some((_, None)) => rendered.push_str(&Highlight::Symbol.wrap(style, &c.to_string())),
+                    None => rendered.push(*c),
+                }
+
+                column += 1;
+            }
+
+            rendered.push('\n');
+
+            let part_sum = line_parts
+                .iter()
+                .filter(|part| parts.contains(part.id()))
+                .map(|part| part.number())
+                .sum::<i32>();
+            let non_part_sum = line_parts
+                .iter()
+                .filter(|part| !parts.contains(part.id()))
+                .map(|part| part.number())
+                .sum::<i32>();
+            let gear_sum = line_symbols.iter().filter_map(|(_, ratio)| *ratio).sum::<i32>();
+
+            legend.push(format!(
+                "Line {line}: parts {part_sum}, non-parts {non_part_sum}, gear ratios {gear_sum}"
+            ));
+        });
+
+        rendered.push('\n');
+        rendered.push_str(&legend.join("\n"));
+        rendered.push('\n');
+
+        rendered
+    }
+}
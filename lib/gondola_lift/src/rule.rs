@@ -0,0 +1,105 @@
+use std::ops::RangeInclusive;
+
+/// How many parts a symbol has to be adjacent to, for a rule to apply
+#[derive(Debug, Clone)]
+pub enum Adjacency {
+    Exact(usize),
+    AtLeast(usize),
+    Range(RangeInclusive<usize>),
+}
+
+impl Adjacency {
+    /// Check if the amount of adjacent parts matches
+    #[inline(always)]
+    pub fn matches(&self, count: usize) -> bool {
+        match self {
+            Self::Exact(n) => count == *n,
+            Self::AtLeast(n) => count >= *n,
+            Self::Range(range) => range.contains(&count),
+        }
+    }
+}
+
+/// A custom reducer function
+pub type ReduceFn = Box<dyn Fn(&[i32]) -> i32>;
+
+/// How the numbers of the adjacent parts are reduced to a single value
+pub enum Reducer {
+    Product,
+    Sum,
+    Max,
+    Custom(ReduceFn),
+}
+
+impl Reducer {
+    /// Create a custom reducer from a closure
+    #[inline(always)]
+    pub fn custom(reducer: impl Fn(&[i32]) -> i32 + 'static) -> Self {
+        Self::Custom(Box::new(reducer))
+    }
+
+    /// Reduce the numbers to a single value
+    #[inline(always)]
+    pub fn reduce(&self, numbers: &[i32]) -> i32 {
+        match self {
+            Self::Product => numbers.iter().product(),
+            Self::Sum => numbers.iter().sum(),
+            Self::Max => numbers.iter().max().copied().unwrap_or(0),
+            Self::Custom(reducer) => reducer(numbers),
+        }
+    }
+}
+
+impl std::fmt::Debug for Reducer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Product => write!(f, "Product"),
+            Self::Sum => write!(f, "Sum"),
+            Self::Max => write!(f, "Max"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// A rule for symbols that act like gears
+///
+/// Every symbol of the rule's character, adjacent to a matching amount of parts,
+/// has the numbers of those parts reduced to a value.
+#[derive(Debug)]
+pub struct GearRule {
+    symbol: char,
+    adjacency: Adjacency,
+    reducer: Reducer,
+}
+
+impl GearRule {
+    /// Create a new gear rule
+    #[inline(always)]
+    pub const fn new(symbol: char, adjacency: Adjacency, reducer: Reducer) -> Self {
+        Self {
+            symbol,
+            adjacency,
+            reducer,
+        }
+    }
+
+    /// The regular gear ratio: `*` symbols adjacent to exactly two parts, multiplied
+    #[inline(always)]
+    pub const fn gear_ratio() -> Self {
+        Self::new('*', Adjacency::Exact(2), Reducer::Product)
+    }
+
+    /// Get the symbol character of the rule
+    #[inline(always)]
+    pub const fn symbol(&self) -> &char {
+        &self.symbol
+    }
+
+    /// Get the value of a symbol with the given adjacent part numbers, if the rule applies
+    #[inline(always)]
+    pub fn apply(&self, numbers: &[i32]) -> Option<i32> {
+        self.adjacency
+            .matches(numbers.len())
+            .then(|| self.reducer.reduce(numbers))
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    part::PartIndex,
+    rule::GearRule,
+    symbol::{Symbol, SymbolSet},
+    EngineSchematic, SchematicError,
+};
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Lines},
+};
+
+/// The totals of a single line of a schematic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineTotals {
+    line: i32,
+    part_sum: i32,
+    gear_ratio: i32,
+}
+
+impl LineTotals {
+    /// Get the line the totals are for
+    #[inline(always)]
+    pub const fn line(&self) -> &i32 {
+        &self.line
+    }
+
+    /// Get the sum of the parts on the line
+    #[inline(always)]
+    pub const fn part_sum(&self) -> &i32 {
+        &self.part_sum
+    }
+
+    /// Get the gear ratio of the gears on the line
+    #[inline(always)]
+    pub const fn gear_ratio(&self) -> &i32 {
+        &self.gear_ratio
+    }
+}
+
+/// A parsed line in the window
+#[derive(Debug)]
+struct WindowLine {
+    line: i32,
+    symbols: Vec<Symbol>,
+    parts: PartIndex,
+}
+
+/// A streaming schematic, processing a three-line sliding window at a time
+///
+/// Adjacency only ever looks at the lines above and below, so the totals of a line are emitted
+/// as soon as the line below it has been read, keeping memory bounded by the width of the schematic.
+pub struct SchematicStream<R: BufRead> {
+    lines: Lines<R>,
+    symbol_set: SymbolSet,
+    rule: GearRule,
+    window: VecDeque<WindowLine>,
+    next_line: i32,
+    next_id: usize,
+    done: bool,
+}
+
+impl<R: BufRead> SchematicStream<R> {
+    /// Create a new stream over the reader
+    #[inline(always)]
+    pub fn new(reader: R) -> Self {
+        Self::with_symbol_set(reader, SymbolSet::default())
+    }
+
+    /// Create a new stream over the reader, using the symbol set to decide which characters are symbols
+    #[inline(always)]
+    pub fn with_symbol_set(reader: R, symbol_set: SymbolSet) -> Self {
+        Self {
+            lines: reader.lines(),
+            symbol_set,
+            rule: GearRule::gear_ratio(),
+            window: VecDeque::with_capacity(3),
+            next_line: 0,
+            next_id: 0,
+            done: false,
+        }
+    }
+
+    /// Use the gear rule, instead of the regular gear ratio
+    #[inline(always)]
+    pub fn with_rule(mut self, rule: GearRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Consume the stream, returning the total part sum and gear ratio
+    pub fn totals(self) -> Result<(i32, i32), SchematicError> {
+        self.into_iter().try_fold((0, 0), |(parts, gears), totals| {
+            let totals = totals?;
+            Ok((parts + totals.part_sum, gears + totals.gear_ratio))
+        })
+    }
+
+    /// Read and parse the next line into the window, returning false at the end of the input
+    fn read_line(&mut self) -> Result<bool, SchematicError> {
+        let Some(contents) = self.lines.next().transpose()? else {
+            return Ok(false);
+        };
+
+        let line = self.next_line;
+        self.next_line += 1;
+
+        let symbols = EngineSchematic::find_symbols((&line, &contents), &self.symbol_set);
+        let (_, parts) = EngineSchematic::find_possible_parts((&line, &contents), &mut self.next_id)?;
+
+        self.window.push_back(WindowLine { line, symbols, parts });
+
+        // Only the lines above and below are needed
+        if self.window.len() > 3 {
+            self.window.pop_front();
+        }
+
+        Ok(true)
+    }
+
+    /// Calculate the totals of a line in the window, using its neighbours
+    fn line_totals(&self, index: usize) -> LineTotals {
+        let current = &self.window[index];
+        let neighbours = self
+            .window
+            .range(index.saturating_sub(1)..(index + 2).min(self.window.len()))
+            .collect::<Vec<_>>();
+
+        let part_sum = current
+            .parts
+            .iter()
+            .filter(|part| {
+                neighbours
+                    .iter()
+                    .flat_map(|line| line.symbols.iter())
+                    .any(|symbol| part.adjacent_to(symbol.coord().column()))
+            })
+            .map(|part| part.number())
+            .sum();
+
+        let gear_ratio = current
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.character() == self.rule.symbol())
+            .filter_map(|symbol| {
+                let numbers = neighbours
+                    .iter()
+                    .flat_map(|line| line.parts.adjacent_to(symbol.coord().column()))
+                    .map(|part| *part.number())
+                    .collect::<Vec<_>>();
+
+                self.rule.apply(&numbers)
+            })
+            .sum();
+
+        LineTotals {
+            line: current.line,
+            part_sum,
+            gear_ratio,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SchematicStream<R> {
+    type Item = Result<LineTotals, SchematicError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            match self.read_line() {
+                // The line before the one just read now has both its neighbours
+                Ok(true) if self.window.len() >= 2 => return Some(Ok(self.line_totals(self.window.len() - 2))),
+                Ok(true) => continue,
+                Ok(false) => {
+                    self.done = true;
+
+                    // The last line has no line below it
+                    return (!self.window.is_empty()).then(|| Ok(self.line_totals(self.window.len() - 1)));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
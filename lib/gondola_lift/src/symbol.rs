@@ -1,17 +1,35 @@
 use crate::SchematicCoordinate;
 
+/// An identifier of a symbol, as its index in the schematic
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SymbolId(usize);
+
+impl SymbolId {
+    /// Create a new symbol id
+    #[inline(always)]
+    pub const fn new(id: usize) -> Self {
+        Self(id)
+    }
+
+    /// Get the id as an index
+    #[inline(always)]
+    pub const fn index(&self) -> usize {
+        self.0
+    }
+}
+
 /// A symbol in a schematic
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Symbol {
     coord: SchematicCoordinate,
-    is_gear: bool,
+    character: char,
 }
 
 impl Symbol {
     /// Create a new symbol
     #[inline(always)]
-    pub const fn new(coord: SchematicCoordinate, is_gear: bool) -> Self {
-        Self { coord, is_gear }
+    pub const fn new(coord: SchematicCoordinate, character: char) -> Self {
+        Self { coord, character }
     }
 
     /// Get the coordinate of the symbol
@@ -20,9 +38,70 @@ impl Symbol {
         &self.coord
     }
 
+    /// Get the character of the symbol
+    #[inline(always)]
+    pub const fn character(&self) -> &char {
+        &self.character
+    }
+
     /// Check if the symbol is a gear
     #[inline(always)]
     pub const fn is_gear(&self) -> bool {
-        self.is_gear
+        self.character == '*'
+    }
+}
+
+/// Decides which characters of a schematic are symbols, and which is the empty filler
+#[derive(Debug, Clone)]
+pub struct SymbolSet {
+    filler: char,
+    symbols: Option<Vec<char>>,
+}
+
+impl Default for SymbolSet {
+    /// Every ASCII punctuation character is a symbol, with `.` as the filler
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new('.')
+    }
+}
+
+impl SymbolSet {
+    /// Create a symbol set where every ASCII punctuation character, except the filler, is a symbol
+    #[inline(always)]
+    pub const fn new(filler: char) -> Self {
+        Self { filler, symbols: None }
+    }
+
+    /// Only treat the given characters as symbols
+    #[inline(always)]
+    pub fn with_symbols(mut self, symbols: &[char]) -> Self {
+        self.symbols = Some(symbols.to_vec());
+        self
+    }
+
+    /// Get the filler character
+    #[inline(always)]
+    pub const fn filler(&self) -> &char {
+        &self.filler
+    }
+
+    /// Check if a character is the filler
+    #[inline(always)]
+    pub fn is_filler(&self, c: char) -> bool {
+        c == self.filler
+    }
+
+    /// Check if a character is a symbol
+    #[inline(always)]
+    pub fn is_symbol(&self, c: char) -> bool {
+        if self.is_filler(c) {
+            return false;
+        }
+
+        match &self.symbols {
+            Some(symbols) => symbols.contains(&c),
+            None => c.is_ascii_punctuation(),
+        }
     }
 }
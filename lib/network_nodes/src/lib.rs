@@ -106,7 +106,7 @@ impl FromStr for NodeAddress {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let nodes = s
-            .trim_matches(&['(', ')'])
+            .trim_matches(['(', ')'])
             .split(", ")
             .map(Node::from_str)
             .collect::<Result<Vec<Node>, NetworkError>>()?;
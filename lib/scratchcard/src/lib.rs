@@ -1,13 +1,30 @@
 use std::{
+    collections::HashSet,
     num::{ParseIntError, TryFromIntError},
     str::FromStr,
 };
 
+/// Scoring and copy rules module
+pub mod rules;
+
+/// Copy cascade tracing module
+pub mod trace;
+
+use rules::{CopyRule, Doubling, NextCards, ScoringRule};
+
 #[derive(Debug)]
 pub enum ScratchCardError {
     Invalid,
     ParseInt(ParseIntError),
     ConvertUsize(TryFromIntError),
+    InvalidNumber(String),
+    DuplicateNumber(i32),
+    MissingColon,
+    UnexpectedColon,
+    MissingSeparator,
+    UnexpectedSeparator,
+    NonSequentialId { expected: usize, found: usize },
+    Overflow,
 }
 
 impl std::error::Error for ScratchCardError {}
@@ -30,6 +47,16 @@ impl std::fmt::Display for ScratchCardError {
             Self::Invalid => write!(f, "Invalid scratchcard"),
             Self::ParseInt(int_err) => write!(f, "Failed to parse integer: {int_err:?}"),
             Self::ConvertUsize(int_err) => write!(f, "Failed to convert integer: {int_err:?}"),
+            Self::InvalidNumber(token) => write!(f, "Invalid number '{token}'"),
+            Self::DuplicateNumber(number) => write!(f, "Duplicate number {number}"),
+            Self::MissingColon => write!(f, "Missing ':' after card id"),
+            Self::UnexpectedColon => write!(f, "Unexpected ':' in numbers"),
+            Self::MissingSeparator => write!(f, "Missing '|' between winning numbers and numbers"),
+            Self::UnexpectedSeparator => write!(f, "Unexpected extra '|'"),
+            Self::Overflow => write!(f, "Points overflowed"),
+            Self::NonSequentialId { expected, found } => {
+                write!(f, "Expected card {expected}, found card {found}")
+            }
         }
     }
 }
@@ -40,13 +67,123 @@ pub struct ScratchCard {
     id: usize,
     winning_numbers: Vec<i32>,
     numbers: Vec<i32>,
+    matches: usize,
     amount: usize,
 }
 
+/// How strictly scratchcards are parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Reject anything malformed (The default)
+    #[default]
+    Strict,
+    /// Skip malformed numbers, and ignore anything else that doesn't fit
+    Lenient,
+}
+
 impl FromStr for ScratchCard {
     type Err = ScratchCardError;
 
+    #[inline(always)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, ParseMode::Strict)
+    }
+}
+
+impl ScratchCard {
+    /// Create a new scratchcard, counting the matching numbers up front
+    #[inline(always)]
+    pub fn new(id: usize, winning_numbers: Vec<i32>, numbers: Vec<i32>) -> Self {
+        let matches = Self::count_matches(&winning_numbers, &numbers);
+
+        Self {
+            id,
+            winning_numbers,
+            numbers,
+            matches,
+            amount: 1,
+        }
+    }
+
+    /// Count the winning numbers that are in the numbers
+    ///
+    /// Card numbers are small, so they are usually looked up in a 128-bit set.
+    /// Falls back to a hashset if any number doesn't fit.
+    fn count_matches(winning_numbers: &[i32], numbers: &[i32]) -> usize {
+        let bits = numbers.iter().try_fold(0u128, |bits, number| {
+            let bit = u32::try_from(*number).ok().and_then(|n| 1u128.checked_shl(n))?;
+            Some(bits | bit)
+        });
+
+        match bits {
+            Some(bits) => winning_numbers
+                .iter()
+                .filter(|wn| {
+                    u32::try_from(**wn)
+                        .ok()
+                        .and_then(|n| 1u128.checked_shl(n))
+                        .is_some_and(|bit| bits & bit != 0)
+                })
+                .count(),
+            None => {
+                let numbers = numbers.iter().collect::<HashSet<_>>();
+                winning_numbers.iter().filter(|wn| numbers.contains(wn)).count()
+            }
+        }
+    }
+
+    /// Parse a scratchcard using the parse mode
+    #[inline(always)]
+    pub fn parse(s: &str, mode: ParseMode) -> Result<Self, ScratchCardError> {
+        match mode {
+            ParseMode::Strict => Self::parse_strict(s),
+            ParseMode::Lenient => Self::parse_lenient(s),
+        }
+    }
+
+    /// Parse a scratchcard, rejecting malformed numbers, duplicates and misplaced separators
+    fn parse_strict(s: &str) -> Result<Self, ScratchCardError> {
+        let (card, numbers) = s.split_once(':').ok_or(ScratchCardError::MissingColon)?;
+
+        if numbers.contains(':') {
+            return Err(ScratchCardError::UnexpectedColon);
+        }
+
+        let id = match card.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+            ["Card", id] => id.parse()?,
+            _ => return Err(ScratchCardError::Invalid),
+        };
+
+        let (winning_numbers, numbers) = numbers.split_once('|').ok_or(ScratchCardError::MissingSeparator)?;
+
+        if numbers.contains('|') {
+            return Err(ScratchCardError::UnexpectedSeparator);
+        }
+
+        let parse_side = |side: &str| {
+            let mut parsed = Vec::new();
+
+            side.split_ascii_whitespace().try_for_each(|token| {
+                let number = token
+                    .parse::<i32>()
+                    .map_err(|_| ScratchCardError::InvalidNumber(token.to_string()))?;
+
+                if parsed.contains(&number) {
+                    return Err(ScratchCardError::DuplicateNumber(number));
+                }
+
+                parsed.push(number);
+                Ok(())
+            })?;
+
+            Ok::<_, ScratchCardError>(parsed)
+        };
+
+        Ok(Self::new(id, parse_side(winning_numbers)?, parse_side(numbers)?))
+    }
+
+    /// Parse a scratchcard, silently skipping malformed numbers
+    fn parse_lenient(s: &str) -> Result<Self, ScratchCardError> {
         let mut split = s.split(':');
 
         let id = split
@@ -56,7 +193,7 @@ impl FromStr for ScratchCard {
             .parse()?;
 
         let mut numbers = split
-            .last()
+            .next_back()
             .ok_or(ScratchCardError::Invalid)?
             .split('|')
             .map(|numbers| {
@@ -67,16 +204,12 @@ impl FromStr for ScratchCard {
             })
             .collect::<Vec<Vec<i32>>>();
 
-        Ok(Self {
-            id,
-            numbers: numbers.pop().ok_or(ScratchCardError::Invalid)?,
-            winning_numbers: numbers.pop().ok_or(ScratchCardError::Invalid)?,
-            amount: 1,
-        })
+        let regular = numbers.pop().ok_or(ScratchCardError::Invalid)?;
+        let winning = numbers.pop().ok_or(ScratchCardError::Invalid)?;
+
+        Ok(Self::new(id, winning, regular))
     }
-}
 
-impl ScratchCard {
     /// Get the ID of the scratchcard
     #[inline(always)]
     pub const fn id(&self) -> &usize {
@@ -90,26 +223,39 @@ impl ScratchCard {
     }
 
     /// Get the total worth of the scratchcard
-    #[inline]
+    #[inline(always)]
     pub fn worth(&self) -> Result<i32, ScratchCardError> {
-        let total_winning = self.total_winning_numbers();
-
-        if total_winning == 0 {
-            return Ok(0);
-        }
-
-        let exponent = (total_winning - 1).try_into()?;
+        self.worth_with(&Doubling)
+    }
 
-        Ok(2i32.pow(exponent))
+    /// Get the total worth of the scratchcard, using the scoring rule
+    #[inline(always)]
+    pub fn worth_with(&self, rule: &impl ScoringRule) -> Result<i32, ScratchCardError> {
+        rule.score(self.total_winning_numbers())
     }
 
     /// Get the total amount of winning numbers
     #[inline(always)]
     fn total_winning_numbers(&self) -> usize {
-        self.winning_numbers
-            .iter()
-            .filter(|wn| self.numbers.contains(wn))
-            .count()
+        self.matches
+    }
+
+    /// Get the amount of winning numbers that are in the numbers
+    #[inline(always)]
+    pub const fn matches(&self) -> &usize {
+        &self.matches
+    }
+
+    /// Get the winning numbers
+    #[inline(always)]
+    pub fn winning_numbers(&self) -> &[i32] {
+        &self.winning_numbers
+    }
+
+    /// Get the numbers
+    #[inline(always)]
+    pub fn numbers(&self) -> &[i32] {
+        &self.numbers
     }
 
     /// Add copies of the scratchcard
@@ -117,6 +263,12 @@ impl ScratchCard {
     pub fn add_copies(&mut self, copies: usize) {
         self.amount += copies;
     }
+
+    /// Reset the amount back to the single original scratchcard
+    #[inline(always)]
+    pub fn reset_copies(&mut self) {
+        self.amount = 1;
+    }
 }
 
 #[derive(Debug)]
@@ -124,30 +276,80 @@ pub struct ScratchCards(Vec<ScratchCard>);
 
 impl ScratchCards {
     /// Get the total worth of all scratchcards
+    #[inline(always)]
     pub fn get_points_worth(&self) -> Result<i32, ScratchCardError> {
-        Ok(self
-            .0
-            .iter()
-            .map(ScratchCard::worth)
-            .collect::<Result<Vec<i32>, ScratchCardError>>()?
-            .iter()
-            .sum())
+        self.get_points_worth_with(&Doubling)
+    }
+
+    /// Get the total worth of all scratchcards, using the scoring rule
+    pub fn get_points_worth_with(&self, rule: &impl ScoringRule) -> Result<i32, ScratchCardError> {
+        self.0.iter().try_fold(0i32, |sum, card| {
+            sum.checked_add(card.worth_with(rule)?)
+                .ok_or(ScratchCardError::Overflow)
+        })
+    }
+
+    /// Get the amount of each scratchcard (including the original) after winning copies, without modifying the cards
+    #[inline(always)]
+    pub fn get_copies(&self) -> Vec<usize> {
+        self.get_copies_with(&NextCards)
+    }
+
+    /// Get the amount of each scratchcard (including the original) after winning copies, using the copy rule
+    pub fn get_copies_with(&self, rule: &impl CopyRule) -> Vec<usize> {
+        let mut copies = vec![1; self.0.len()];
+
+        self.0.iter().enumerate().for_each(|(key, card)| {
+            let amount = copies[key];
+
+            rule.targets(key, card.total_winning_numbers(), self.0.len())
+                .into_iter()
+                .for_each(|target| {
+                    // Skip targets outside of the scratchcards, as custom rules may return them
+                    if let Some(copies) = copies.get_mut(target) {
+                        *copies += amount;
+                    }
+                });
+        });
+
+        copies
+    }
+
+    /// Get the total amount of scratchcards, with respect to copies
+    #[inline(always)]
+    pub fn get_total_cards(&self) -> usize {
+        self.get_total_cards_with(&NextCards)
+    }
+
+    /// Get the total amount of scratchcards, with respect to copies won using the copy rule
+    #[inline(always)]
+    pub fn get_total_cards_with(&self, rule: &impl CopyRule) -> usize {
+        self.get_copies_with(rule).iter().sum()
+    }
+
+    /// Reset the amount of every scratchcard back to the original
+    #[inline(always)]
+    pub fn reset_copies(&mut self) {
+        self.0.iter_mut().for_each(ScratchCard::reset_copies);
     }
 
     /// Calculate the total amount of scratchcards, with respect to copies
+    ///
+    /// The copies are accumulated into the cards, so calling this twice gives a wrong result,
+    /// unless [`ScratchCards::reset_copies`] is called in between.
+    #[deprecated(note = "accumulates into the cards, use `get_copies` or `get_total_cards` instead")]
     pub fn calculate_copies_and_get_total(&mut self) -> Result<usize, ScratchCardError> {
         (0..self.0.len()).for_each(|key| {
             let Some(card) = self.0.get(key) else {
                 panic!("Key exceeded iterator length!")
             };
 
-            let winnings = card.total_winning_numbers();
+            let amount = *card.amount();
 
-            if winnings > 0 {
-                let range = (key + 1)..=(key + winnings);
-                let amount = *card.amount();
-                range.for_each(|i| self.0.get_mut(i).map(|card| card.add_copies(amount)).unwrap_or(()))
-            }
+            NextCards
+                .targets(key, card.total_winning_numbers(), self.0.len())
+                .into_iter()
+                .for_each(|i| self.0[i].add_copies(amount));
         });
 
         Ok(self.0.iter().map(|card| card.amount).sum())
@@ -160,15 +362,39 @@ impl FromIterator<ScratchCard> for ScratchCards {
     }
 }
 
+impl ScratchCards {
+    /// Parse scratchcards using the parse mode
+    ///
+    /// In strict mode, the card ids also have to be sequential, starting at 1.
+    pub fn parse(s: &str, mode: ParseMode) -> Result<Self, ScratchCardError> {
+        let cards = s
+            .lines()
+            .map(|line| ScratchCard::parse(line, mode))
+            .collect::<Result<Vec<ScratchCard>, _>>()?;
+
+        if mode == ParseMode::Strict {
+            cards.iter().zip(1..).try_for_each(|(card, expected)| {
+                if card.id != expected {
+                    return Err(ScratchCardError::NonSequentialId {
+                        expected,
+                        found: card.id,
+                    });
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(Self::from_iter(cards))
+    }
+}
+
 impl FromStr for ScratchCards {
     type Err = ScratchCardError;
 
+    #[inline(always)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self::from_iter(
-            s.lines()
-                .map(ScratchCard::from_str)
-                .collect::<Result<Vec<ScratchCard>, _>>()?,
-        ))
+        Self::parse(s, ParseMode::Strict)
     }
 }
 
@@ -190,6 +416,7 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
     }
 
     #[test]
+    #[allow(deprecated)]
     fn copies() {
         let mut cards = ScratchCards::from_str(EXAMPLE).expect("Failed to create scratchcards");
         let total = cards
@@ -197,4 +424,138 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
             .expect("Failed to calculate copies");
         assert_eq!(total, 30)
     }
+
+    #[test]
+    fn pure_copies() {
+        let cards = ScratchCards::from_str(EXAMPLE).expect("Failed to create scratchcards");
+        assert_eq!(cards.get_copies(), vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(cards.get_total_cards(), 30);
+        assert_eq!(cards.get_total_cards(), 30);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn reset_copies() {
+        let mut cards = ScratchCards::from_str(EXAMPLE).expect("Failed to create scratchcards");
+        let _ = cards.calculate_copies_and_get_total();
+        cards.reset_copies();
+
+        let total = cards
+            .calculate_copies_and_get_total()
+            .expect("Failed to calculate copies");
+        assert_eq!(total, 30);
+    }
+
+    #[test]
+    fn strict_parsing() {
+        let parse = |s| ScratchCard::from_str(s);
+
+        assert!(matches!(
+            parse("Card 1: 41 4x | 83"),
+            Err(ScratchCardError::InvalidNumber(token)) if token == "4x"
+        ));
+        assert!(matches!(
+            parse("Card 1: 41 41 | 83"),
+            Err(ScratchCardError::DuplicateNumber(41))
+        ));
+        assert!(matches!(
+            parse("Card 1: 41 48 83"),
+            Err(ScratchCardError::MissingSeparator)
+        ));
+        assert!(matches!(
+            parse("Card 1: 41 | 48 | 83"),
+            Err(ScratchCardError::UnexpectedSeparator)
+        ));
+        assert!(matches!(parse("Card 1 41 | 83"), Err(ScratchCardError::MissingColon)));
+        assert!(matches!(
+            parse("Card 1: 41: 48 | 83"),
+            Err(ScratchCardError::UnexpectedColon)
+        ));
+        assert!(matches!(
+            ScratchCards::from_str("Card 1: 41 | 83\nCard 3: 41 | 83"),
+            Err(ScratchCardError::NonSequentialId { expected: 2, found: 3 })
+        ));
+
+        let cards = ScratchCards::parse("Card 1: 41 4x | 41\nCard 3: 41 | 83", ParseMode::Lenient)
+            .expect("Failed to parse leniently");
+        assert_eq!(cards.get_points_worth().expect("Failed to calculate worth"), 1);
+    }
+
+    #[test]
+    fn rules() {
+        use rules::{Capped, Fibonacci, Linear, WrapAround};
+
+        let cards = ScratchCards::from_str(EXAMPLE).expect("Failed to create scratchcards");
+
+        // Matches are 4, 2, 2, 1, 0, 0
+        assert_eq!(cards.get_points_worth_with(&Linear(1)).expect("Failed to score"), 9);
+        assert_eq!(
+            cards.get_points_worth_with(&Fibonacci).expect("Failed to score"),
+            5 + 2 + 2 + 1
+        );
+
+        assert_eq!(cards.get_copies_with(&Capped(1)), vec![1, 2, 3, 4, 5, 1]);
+        assert_eq!(cards.get_total_cards_with(&Capped(1)), 16);
+
+        let cards = ScratchCards::from_str(
+            "Card 1: 1 | 2
+Card 2: 1 | 2
+Card 3: 1 2 | 1 2",
+        )
+        .expect("Failed to create scratchcards");
+        assert_eq!(cards.get_copies_with(&WrapAround), vec![2, 2, 1]);
+        assert_eq!(cards.get_copies(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn out_of_range_targets() {
+        use rules::CopyRule;
+
+        /// Copies the next card, without checking if it exists
+        struct Unchecked;
+
+        impl CopyRule for Unchecked {
+            fn targets(&self, index: usize, _matches: usize, len: usize) -> Vec<usize> {
+                vec![len, index + 1]
+            }
+        }
+
+        let cards = ScratchCards::from_str(EXAMPLE).expect("Failed to create scratchcards");
+
+        assert_eq!(cards.get_copies_with(&Unchecked), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            cards
+                .trace_with(&rules::Doubling, &Unchecked)
+                .expect("Failed to trace")
+                .total(),
+            21
+        );
+    }
+
+    #[test]
+    fn match_counting() {
+        assert_eq!(*ScratchCard::new(1, vec![0, 5, 127], vec![127, 0, 3]).matches(), 2);
+        assert_eq!(*ScratchCard::new(1, vec![128, 5, -1], vec![128, 5, 6]).matches(), 2);
+        assert_eq!(*ScratchCard::new(1, vec![128, 5, -1], vec![1, 5, 6]).matches(), 1);
+        assert_eq!(*ScratchCard::new(1, vec![1000, 5], vec![1000, -1, 5]).matches(), 2);
+    }
+
+    #[test]
+    fn trace() {
+        let cards = ScratchCards::from_str(EXAMPLE).expect("Failed to create scratchcards");
+        let trace = cards.trace().expect("Failed to trace");
+
+        assert_eq!(trace.total(), 30);
+
+        let card = &trace.cards()[4];
+        assert_eq!(
+            (*card.id(), *card.matches(), *card.points(), *card.amount()),
+            (5, 0, 0, 14)
+        );
+        assert_eq!(card.sources(), &[(1, 1), (3, 4), (4, 8)]);
+
+        let table = trace.to_string();
+        assert!(table.contains("     5 |       0 |      0 |     14 | 1x1, 3x4, 4x8"));
+        assert!(table.ends_with("Total: 30"));
+    }
 }
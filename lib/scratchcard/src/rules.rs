@@ -0,0 +1,110 @@
+use crate::ScratchCardError;
+
+/// A rule for how many points a scratchcard is worth
+pub trait ScoringRule {
+    /// Get the points for the amount of matching numbers
+    fn score(&self, matches: usize) -> Result<i32, ScratchCardError>;
+}
+
+/// A rule for which scratchcards are copied when a scratchcard wins
+pub trait CopyRule {
+    /// Get the indices of the scratchcards that receive a copy, when the scratchcard at `index` has `matches` matching numbers
+    ///
+    /// `len` is the total amount of scratchcards. Targets should be below `len`, as any others
+    /// are skipped.
+    fn targets(&self, index: usize, matches: usize, len: usize) -> Vec<usize>;
+}
+
+/// The first match is worth one point, and each match after doubles it (The default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Doubling;
+
+impl ScoringRule for Doubling {
+    #[inline(always)]
+    fn score(&self, matches: usize) -> Result<i32, ScratchCardError> {
+        if matches == 0 {
+            return Ok(0);
+        }
+
+        let exponent = (matches - 1).try_into()?;
+
+        2i32.checked_pow(exponent).ok_or(ScratchCardError::Overflow)
+    }
+}
+
+/// Each match is worth the same amount of points
+#[derive(Debug, Clone, Copy)]
+pub struct Linear(pub i32);
+
+impl ScoringRule for Linear {
+    #[inline(always)]
+    fn score(&self, matches: usize) -> Result<i32, ScratchCardError> {
+        i32::try_from(matches)?
+            .checked_mul(self.0)
+            .ok_or(ScratchCardError::Overflow)
+    }
+}
+
+/// The points follow the Fibonacci sequence: `1, 2, 3, 5, 8, ...`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fibonacci;
+
+impl ScoringRule for Fibonacci {
+    #[inline(always)]
+    fn score(&self, matches: usize) -> Result<i32, ScratchCardError> {
+        if matches == 0 {
+            return Ok(0);
+        }
+
+        let (mut current, mut next) = (1i32, 2i32);
+
+        for _ in 1..matches {
+            (current, next) = (next, current.checked_add(next).ok_or(ScratchCardError::Overflow)?);
+        }
+
+        Ok(current)
+    }
+}
+
+/// Winning `N` matches copies the next `N` scratchcards, discarding copies past the end (The default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NextCards;
+
+impl CopyRule for NextCards {
+    #[inline(always)]
+    fn targets(&self, index: usize, matches: usize, len: usize) -> Vec<usize> {
+        ((index + 1)..(index + 1 + matches).min(len)).collect()
+    }
+}
+
+/// Winning `N` matches copies the next `N` scratchcards, wrapping around to the start at the end
+///
+/// Scratchcards are only processed once, so copies that wrap around to earlier scratchcards
+/// don't win any further copies.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WrapAround;
+
+impl CopyRule for WrapAround {
+    #[inline(always)]
+    fn targets(&self, index: usize, matches: usize, len: usize) -> Vec<usize> {
+        if len == 0 {
+            return Vec::new();
+        }
+
+        // A scratchcard never copies itself
+        (1..=matches.min(len - 1))
+            .map(|offset| (index + offset) % len)
+            .collect()
+    }
+}
+
+/// Winning `N` matches copies the next `N` scratchcards, but never more than the limit
+#[derive(Debug, Clone, Copy)]
+pub struct Capped(pub usize);
+
+impl CopyRule for Capped {
+    #[inline(always)]
+    fn targets(&self, index: usize, matches: usize, len: usize) -> Vec<usize> {
+        NextCards.targets(index, matches.min(self.0), len)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    rules::{CopyRule, Doubling, NextCards, ScoringRule},
+    ScratchCardError, ScratchCards,
+};
+
+/// A trace of a single scratchcard in the copy cascade
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardTrace {
+    id: usize,
+    matches: usize,
+    points: i32,
+    amount: usize,
+    sources: Vec<(usize, usize)>,
+}
+
+impl CardTrace {
+    /// Get the ID of the scratchcard
+    #[inline(always)]
+    pub const fn id(&self) -> &usize {
+        &self.id
+    }
+
+    /// Get the amount of matching numbers
+    #[inline(always)]
+    pub const fn matches(&self) -> &usize {
+        &self.matches
+    }
+
+    /// Get the points the scratchcard is worth
+    #[inline(always)]
+    pub const fn points(&self) -> &i32 {
+        &self.points
+    }
+
+    /// Get the total amount of the scratchcard, including the original
+    #[inline(always)]
+    pub const fn amount(&self) -> &usize {
+        &self.amount
+    }
+
+    /// Get the copies received, as `(card id, copies)` for each card that gave copies
+    #[inline(always)]
+    pub fn sources(&self) -> &[(usize, usize)] {
+        &self.sources
+    }
+}
+
+/// A trace of the whole copy cascade
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CascadeTrace(Vec<CardTrace>);
+
+impl CascadeTrace {
+    /// Get the traces of each scratchcard
+    #[inline(always)]
+    pub fn cards(&self) -> &[CardTrace] {
+        &self.0
+    }
+
+    /// Get the total amount of scratchcards
+    #[inline(always)]
+    pub fn total(&self) -> usize {
+        self.0.iter().map(|card| card.amount).sum()
+    }
+}
+
+impl std::fmt::Display for CascadeTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>6} | {:>7} | {:>6} | {:>6} | From",
+            "Card", "Matches", "Points", "Copies"
+        )?;
+        writeln!(f, "{}", "-".repeat(45))?;
+
+        for card in &self.0 {
+            let sources = card
+                .sources
+                .iter()
+                .map(|(id, copies)| format!("{id}x{copies}"))
+                .collect::<Vec<_>>();
+
+            writeln!(
+                f,
+                "{:>6} | {:>7} | {:>6} | {:>6} | {}",
+                card.id,
+                card.matches,
+                card.points,
+                card.amount,
+                sources.join(", ")
+            )?;
+        }
+
+        write!(f, "Total: {}", self.total())
+    }
+}
+
+impl ScratchCards {
+    /// Trace where each scratchcard's copies came from
+    #[inline(always)]
+    pub fn trace(&self) -> Result<CascadeTrace, ScratchCardError> {
+        self.trace_with(&Doubling, &NextCards)
+    }
+
+    /// Trace where each scratchcard's copies came from, using the scoring and copy rules
+    pub fn trace_with(
+        &self,
+        scoring: &impl ScoringRule,
+        copying: &impl CopyRule,
+    ) -> Result<CascadeTrace, ScratchCardError> {
+        let mut traces = self
+            .0
+            .iter()
+            .map(|card| {
+                Ok(CardTrace {
+                    id: *card.id(),
+                    matches: *card.matches(),
+                    points: card.worth_with(scoring)?,
+                    amount: 1,
+                    sources: Vec::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, ScratchCardError>>()?;
+
+        (0..traces.len()).for_each(|key| {
+            let (id, amount) = (traces[key].id, traces[key].amount);
+
+            copying
+                .targets(key, traces[key].matches, traces.len())
+                .into_iter()
+                .for_each(|target| {
+                    // Skip targets outside of the scratchcards, as custom rules may return them
+                    if let Some(trace) = traces.get_mut(target) {
+                        trace.amount += amount;
+                        trace.sources.push((id, amount));
+                    }
+                });
+        });
+
+        Ok(CascadeTrace(traces))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{CalibrationError, CalibrationResult};
+
+/// A rule describing how the digits of a calibration line are combined into a single value
+///
+/// Any closure taking the digits and returning a [`CalibrationResult`] is also a rule,
+/// so variant calibration formats can be supported without new types.
+pub trait CombineRule {
+    /// Combine the digits (in order of appearance) into a single value
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32>;
+}
+
+impl<F> CombineRule for F
+where
+    F: Fn(&[i32]) -> CalibrationResult<i32>,
+{
+    #[inline(always)]
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32> {
+        self(digits)
+    }
+}
+
+/// Concatenates the given digits into a single number, checking for overflow
+///
+/// e.g. `1`, `2` and `3` becomes `123`
+#[inline(always)]
+fn concatenate<'a>(digits: impl IntoIterator<Item = &'a i32>) -> CalibrationResult<i32> {
+    digits.into_iter().try_fold(0i32, |value, digit| {
+        value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(*digit))
+            .ok_or(CalibrationError::Overflow)
+    })
+}
+
+/// Combines the first and last digit (The default rule)
+///
+/// e.g. `1`, `5` and `9` becomes `19`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FirstLast;
+
+impl CombineRule for FirstLast {
+    #[inline(always)]
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32> {
+        let first = digits.first().ok_or(CalibrationError::NoNumbers)?;
+        let last = digits.last().unwrap_or(first);
+        concatenate([first, last])
+    }
+}
+
+/// Concatenates all digits
+///
+/// e.g. `1`, `5` and `9` becomes `159`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllDigits;
+
+impl CombineRule for AllDigits {
+    #[inline(always)]
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32> {
+        if digits.is_empty() {
+            return Err(CalibrationError::NoNumbers);
+        }
+
+        concatenate(digits)
+    }
+}
+
+/// Concatenates the first `N` digits, or all of them if there are fewer
+///
+/// e.g. `FirstDigits(2)` turns `1`, `5` and `9` into `15`
+///
+/// Taking zero digits is invalid, and fails with [`CalibrationError::NoDigitsTaken`].
+#[derive(Debug, Clone, Copy)]
+pub struct FirstDigits(pub usize);
+
+impl CombineRule for FirstDigits {
+    #[inline(always)]
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32> {
+        if self.0 == 0 {
+            return Err(CalibrationError::NoDigitsTaken);
+        }
+
+        if digits.is_empty() {
+            return Err(CalibrationError::NoNumbers);
+        }
+
+        concatenate(digits.iter().take(self.0))
+    }
+}
+
+/// Sums all digits
+///
+/// e.g. `1`, `5` and `9` becomes `15`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DigitSum;
+
+impl CombineRule for DigitSum {
+    #[inline(always)]
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32> {
+        if digits.is_empty() {
+            return Err(CalibrationError::NoNumbers);
+        }
+
+        digits
+            .iter()
+            .try_fold(0i32, |sum, digit| sum.checked_add(*digit))
+            .ok_or(CalibrationError::Overflow)
+    }
+}
+
+/// Takes the largest digit
+///
+/// e.g. `1`, `5` and `9` becomes `9`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LargestDigit;
+
+impl CombineRule for LargestDigit {
+    #[inline(always)]
+    fn combine(&self, digits: &[i32]) -> CalibrationResult<i32> {
+        digits.iter().max().copied().ok_or(CalibrationError::NoNumbers)
+    }
+}
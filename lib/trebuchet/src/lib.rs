@@ -3,6 +3,11 @@ use std::{
     str::FromStr,
 };
 
+/// Combine rules module
+pub mod combine;
+
+use combine::{CombineRule, FirstLast};
+
 const NUMBER_STRINGS: [&str; 10] = [
     "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
 ];
@@ -15,6 +20,8 @@ pub enum CalibrationError {
     ParseInt(ParseIntError),
     ConvertInt(TryFromIntError),
     NoNumbers,
+    Overflow,
+    NoDigitsTaken,
 }
 
 impl std::error::Error for CalibrationError {}
@@ -35,6 +42,8 @@ impl std::fmt::Display for CalibrationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::NoNumbers => write!(f, "No numbers found"),
+            Self::Overflow => write!(f, "Combined value overflowed"),
+            Self::NoDigitsTaken => write!(f, "Rule takes no digits"),
             Self::ParseInt(int_err) => write!(f, "Failed to parse integer: {int_err:?}"),
             Self::ConvertInt(int_err) => write!(f, "Failed to convert integer: {int_err:?}"),
         }
@@ -74,26 +83,36 @@ impl CalibrationValue {
     /// Get the value of the **numeric** calibration numbers
     #[inline(always)]
     pub fn get_numeric_value(&self) -> CalibrationResult<i32> {
-        let numerics = self.0.iter().filter(|n| CalibrationNumber::is_numeric(n));
-        Self::get_number(numerics)
+        self.get_numeric_value_with(&FirstLast)
     }
 
     /// Get the value of the calibration value
     #[inline(always)]
     pub fn get_value(&self) -> CalibrationResult<i32> {
-        Self::get_number(self.0.iter())
+        self.get_value_with(&FirstLast)
+    }
+
+    /// Get the value of the **numeric** calibration numbers, combined using the provided rule
+    #[inline(always)]
+    pub fn get_numeric_value_with(&self, rule: &impl CombineRule) -> CalibrationResult<i32> {
+        let numerics = self.0.iter().filter(|n| CalibrationNumber::is_numeric(n));
+        Self::get_number(numerics, rule)
     }
 
-    /// Gets the first and last values of the provided iterator, and combines them into a single number (Not a sum)
-    ///
-    /// e.g. `1` and `9` becomes `19`
-    ///
+    /// Get the value of the calibration value, combined using the provided rule
     #[inline(always)]
-    fn get_number<'a>(mut iter: impl Iterator<Item = &'a CalibrationNumber>) -> CalibrationResult<i32> {
-        let first = iter.next().ok_or(CalibrationError::NoNumbers)?;
-        let last = iter.last().unwrap_or(first);
-        let num = format!("{}{}", first.as_int(), last.as_int()).parse()?;
-        Ok(num)
+    pub fn get_value_with(&self, rule: &impl CombineRule) -> CalibrationResult<i32> {
+        Self::get_number(self.0.iter(), rule)
+    }
+
+    /// Collects the digits of the provided iterator, and combines them into a single number using the rule
+    #[inline(always)]
+    fn get_number<'a>(
+        iter: impl Iterator<Item = &'a CalibrationNumber>,
+        rule: &impl CombineRule,
+    ) -> CalibrationResult<i32> {
+        let digits = iter.map(CalibrationNumber::as_int).copied().collect::<Vec<_>>();
+        rule.combine(&digits)
     }
 }
 
@@ -147,24 +166,30 @@ impl Trebuchet {
 
     /// Get the sum of all calibration values
     pub fn get_calibration_sum(&self) -> CalibrationResult<i32> {
-        Ok(self
-            .0
-            .iter()
-            .map(CalibrationValue::get_value)
-            .collect::<CalibrationResult<Vec<i32>>>()?
-            .iter()
-            .sum())
+        self.get_calibration_sum_with(&FirstLast)
     }
 
     /// Get the sum of all **numeric** calibration values
     pub fn get_numeric_calibration_sum(&self) -> CalibrationResult<i32> {
-        Ok(self
-            .0
-            .iter()
-            .map(CalibrationValue::get_numeric_value)
-            .collect::<CalibrationResult<Vec<i32>>>()?
-            .iter()
-            .sum())
+        self.get_numeric_calibration_sum_with(&FirstLast)
+    }
+
+    /// Get the sum of all calibration values, combined using the provided rule
+    pub fn get_calibration_sum_with(&self, rule: &impl CombineRule) -> CalibrationResult<i32> {
+        Self::checked_sum(self.0.iter().map(|value| value.get_value_with(rule)))
+    }
+
+    /// Get the sum of all **numeric** calibration values, combined using the provided rule
+    pub fn get_numeric_calibration_sum_with(&self, rule: &impl CombineRule) -> CalibrationResult<i32> {
+        Self::checked_sum(self.0.iter().map(|value| value.get_numeric_value_with(rule)))
+    }
+
+    /// Sums the values, checking for overflow
+    #[inline(always)]
+    fn checked_sum(mut values: impl Iterator<Item = CalibrationResult<i32>>) -> CalibrationResult<i32> {
+        values.try_fold(0i32, |sum, value| {
+            sum.checked_add(value?).ok_or(CalibrationError::Overflow)
+        })
     }
 }
 
@@ -261,4 +286,40 @@ mod tests {
             76
         );
     }
+
+    #[test]
+    fn test_combine_rules() {
+        use combine::{AllDigits, DigitSum, FirstDigits, LargestDigit};
+
+        let value = CalibrationValue::from_str("a1b2three4nine").expect("Failed to parse calibration value");
+
+        assert_eq!(value.get_value_with(&FirstLast).expect("Failed to get value"), 19);
+        assert_eq!(value.get_value_with(&AllDigits).expect("Failed to get value"), 12349);
+        assert_eq!(value.get_value_with(&FirstDigits(3)).expect("Failed to get value"), 123);
+        assert_eq!(value.get_value_with(&DigitSum).expect("Failed to get value"), 19);
+        assert_eq!(value.get_value_with(&LargestDigit).expect("Failed to get value"), 9);
+        assert_eq!(
+            value.get_numeric_value_with(&AllDigits).expect("Failed to get value"),
+            124
+        );
+        assert_eq!(
+            value
+                .get_value_with(&|digits: &[i32]| Ok(digits.len() as i32))
+                .expect("Failed to get value"),
+            5
+        );
+        assert!(matches!(
+            value.get_value_with(&FirstDigits(0)),
+            Err(CalibrationError::NoDigitsTaken)
+        ));
+    }
+
+    #[test]
+    fn test_combine_overflow() {
+        let value = CalibrationValue::from_str("12345678901").expect("Failed to parse calibration value");
+        assert!(matches!(
+            value.get_value_with(&combine::AllDigits),
+            Err(CalibrationError::Overflow)
+        ));
+    }
 }